@@ -0,0 +1,232 @@
+//! `#[derive(Parse)]` for `markdown_parser::parse::Parse`.
+//!
+//! Structs parse their fields in order, enums try their variants in order.
+//! Behavior is tuned with `#[parse(...)]` attributes:
+//!
+//! - On a struct or variant: `token = "..."` matches a token before any
+//!   field, `skip_whitespace` skips whitespace before each token and field.
+//! - On a field: `token = "..."` (a `Text` field), `char_if = predicate` (a
+//!   `FatChar` field), `with = path` (a `fn() -> Parser<T>`), `many` or `some`
+//!   to repeat the field parser and collect into the field type, `before` and
+//!   `after` to match surrounding tokens, and `skip` to fill the field with
+//!   `Default::default()` instead of parsing it.
+//!
+//! Fields without a parser attribute use their own `Parse` impl.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Expr, Fields,
+    GenericArgument, LitStr, Path, PathArguments, Result, Type,
+};
+
+#[proc_macro_derive(Parse, attributes(parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// ATTRIBUTES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Default)]
+struct Attributes {
+    token: Option<LitStr>,
+    char_if: Option<Expr>,
+    with: Option<Path>,
+    repeat: Option<Repeat>,
+    before: Option<LitStr>,
+    after: Option<LitStr>,
+    skip: bool,
+    skip_whitespace: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Repeat {
+    Many,
+    Some,
+}
+
+impl Attributes {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|x| x.path().is_ident("parse")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("token") {
+                    result.token = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("char_if") {
+                    result.char_if = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("with") {
+                    result.with = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("before") {
+                    result.before = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("after") {
+                    result.after = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("many") {
+                    result.repeat = Some(Repeat::Many);
+                } else if meta.path.is_ident("some") {
+                    result.repeat = Some(Repeat::Some);
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else if meta.path.is_ident("skip_whitespace") {
+                    result.skip_whitespace = true;
+                } else {
+                    return Err(meta.error("unknown `parse` attribute"))
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// EXPANSION
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn expand(mut input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let container = Attributes::parse(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) => sequence(quote!(#name), &data.fields, &container)?,
+        Data::Enum(data) => {
+            let variants = data.variants
+                .iter()
+                .map(|variant| {
+                    let mut attributes = Attributes::parse(&variant.attrs)?;
+                    attributes.skip_whitespace |= container.skip_whitespace;
+                    let ident = &variant.ident;
+                    sequence(quote!(#name::#ident), &variant.fields, &attributes)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            quote!(::markdown_parser::parser::Parser::options(vec![#(#variants),*]))
+        }
+        Data::Union(_) => return Err(Error::new_spanned(&input.ident, "`Parse` cannot be derived for unions")),
+    };
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::markdown_parser::parse::Parse));
+        param.bounds.push(parse_quote!(::core::clone::Clone));
+        param.bounds.push(parse_quote!(::markdown_parser::shared::Shareable));
+        param.bounds.push(parse_quote!('static));
+    }
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::markdown_parser::parse::Parse for #name #type_generics #where_clause {
+            fn parser() -> ::markdown_parser::parser::Parser<Self> {
+                #body
+            }
+        }
+    })
+}
+
+/// Parses `fields` in order and builds them with `constructor`.
+fn sequence(constructor: TokenStream2, fields: &Fields, attributes: &Attributes) -> Result<TokenStream2> {
+    if fields.is_empty() && attributes.token.is_none() {
+        let message = "unit structs and variants need a `#[parse(token = \"...\")]` attribute";
+        return Err(Error::new_spanned(constructor, message))
+    }
+    let mut chain = quote!(::markdown_parser::parser::UnitParser::unit());
+    let mut pattern = quote!(());
+    if let Some(token) = attributes.token.as_ref() {
+        let token = whitespace(quote!(::markdown_parser::parser::TextParser::token(#token)), attributes);
+        chain = quote!(#chain.ignore(#token));
+    }
+    let mut values = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let field_attributes = Attributes::parse(&field.attrs)?;
+        if field_attributes.skip {
+            values.push(quote!(::core::default::Default::default()));
+            continue
+        }
+        let variable = format_ident!("field_{index}");
+        let parser = field_parser(&field.ty, &field_attributes, attributes)?;
+        chain = quote!(#chain.and(#parser));
+        pattern = quote!((#pattern, #variable));
+        values.push(quote!(#variable));
+    }
+    let value = match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|x| &x.ident);
+            quote!(#constructor { #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#constructor(#(#values),*)),
+        Fields::Unit => quote!(#constructor),
+    };
+    Ok(quote!(#chain.map(|#pattern| #value)))
+}
+
+fn field_parser(ty: &Type, field: &Attributes, item: &Attributes) -> Result<TokenStream2> {
+    let explicit = if let Some(token) = field.token.as_ref() {
+        Some(quote!(::markdown_parser::parser::TextParser::token(#token)))
+    } else if let Some(predicate) = field.char_if.as_ref() {
+        Some(quote!(::markdown_parser::parser::CharParser::char_if(#predicate)))
+    } else {
+        field.with.as_ref().map(|path| quote!(#path()))
+    };
+    let mut parser = match (explicit, field.repeat) {
+        (Some(parser), None) => parser,
+        (Some(parser), Some(repeat)) => {
+            let repeated = repeated(parser, repeat);
+            quote!(#repeated.map(|xs| xs.into_iter().collect::<#ty>()))
+        }
+        (None, None) => quote!(::markdown_parser::parser::Parser::lazy(<#ty as ::markdown_parser::parse::Parse>::parser)),
+        (None, Some(repeat)) => {
+            let Some(element) = vec_element(ty) else {
+                let message = "`many` and `some` need a `Vec<T>` field, or an explicit parser";
+                return Err(Error::new_spanned(ty, message))
+            };
+            let parser = quote!(::markdown_parser::parser::Parser::lazy(<#element as ::markdown_parser::parse::Parse>::parser));
+            let parser = whitespace(parser, item);
+            repeated(parser, repeat)
+        }
+    };
+    parser = whitespace(parser, item);
+    if let Some(before) = field.before.as_ref() {
+        let before = whitespace(quote!(::markdown_parser::parser::TextParser::token(#before)), item);
+        parser = quote!(#before.keep(#parser));
+    }
+    if let Some(after) = field.after.as_ref() {
+        let after = whitespace(quote!(::markdown_parser::parser::TextParser::token(#after)), item);
+        parser = quote!(#parser.ignore(#after));
+    }
+    Ok(parser)
+}
+
+fn repeated(parser: TokenStream2, repeat: Repeat) -> TokenStream2 {
+    match repeat {
+        Repeat::Many => quote!(#parser.many()),
+        Repeat::Some => quote!(#parser.some()),
+    }
+}
+
+/// Skips leading whitespace before `parser` when the item asks for it.
+fn whitespace(parser: TokenStream2, item: &Attributes) -> TokenStream2 {
+    if !item.skip_whitespace {
+        return parser
+    }
+    quote! {
+        ::markdown_parser::parser::CharParser::char_if(char::is_whitespace)
+            .many()
+            .keep(#parser)
+    }
+}
+
+/// `T` for a field of type `Vec<T>`.
+fn vec_element(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Vec" {
+        return None
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(element) => Some(element),
+        _ => None,
+    }
+}
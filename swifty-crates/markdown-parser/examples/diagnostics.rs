@@ -0,0 +1,32 @@
+use markdown_parser::parser::*;
+use markdown_parser::report::{Label, Renderer, Report};
+use markdown_parser::text::{PositionIndex, Text};
+
+fn entry() -> Parser<String> {
+    let word = CharParser::char_if(char::is_alphanumeric).some().map(Text::from_iter);
+    word.clone()
+        .ignore(CharParser::char('='))
+        .and(word)
+        .ignore(CharParser::char('\n').void().or(UnitParser::end_of_input()))
+        .map(|(key, value)| format!("{key}={value}"))
+        .context("entry")
+}
+
+fn main() {
+    let renderer = Renderer::default().file_name("settings.txt");
+    let source = "alpha=1\nbeta?2\ngamma=3\ndelta=";
+    let parser = entry()
+        .recover_to_next_line(|skipped| format!("<error: {skipped:?}>"))
+        .many();
+    let (_, diagnostics) = Parser::evaluate_partial(source, parser);
+    for diagnostic in diagnostics.iter() {
+        println!("{}\n", renderer.render(source, &Report::from(diagnostic)));
+    }
+    // Reports aren't limited to parse errors, e.g. a lint finding:
+    let start = PositionIndex { line_offset: 2, column_offset: 0, ..PositionIndex::ZERO };
+    let end = PositionIndex { line_offset: 2, column_offset: 5, ..PositionIndex::ZERO };
+    let lint = Report::warning("key is not in the schema")
+        .with_label(Label::primary(start, end, "unknown key"))
+        .with_note("known keys are alpha, beta and delta");
+    println!("{}", renderer.use_color(false).render(source, &lint));
+}
@@ -0,0 +1,65 @@
+use markdown_parser::parser::*;
+use markdown_parser::text::{FatChar, Text};
+use tree_formatter::{PrettyTree, PrettyTreePrinter, ToPrettyTree};
+
+#[derive(Debug, Clone)]
+enum Node {
+    Group(Vec<Node>),
+    Word(String),
+}
+
+impl ToPrettyTree for Node {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        match self {
+            Node::Group(children) => PrettyTree::branch_of("[]", children),
+            Node::Word(word) => PrettyTree::string(word),
+        }
+    }
+}
+
+fn spaces() -> Parser<Vec<FatChar>> {
+    CharParser::char(' ').many()
+}
+
+/// `node := word | "[" node* "]"`
+fn grammar() -> Parser<Node> {
+    Parser::recursive(|node| {
+        let word = CharParser::char_if(char::is_alphanumeric)
+            .some()
+            .map(|xs| Node::Word(Text::from_iter(xs).to_string()));
+        let group = node
+            .ignore(spaces())
+            .many()
+            .between2(CharParser::char('[').ignore(spaces()), CharParser::char(']'))
+            .map(|(_, children, _)| Node::Group(children));
+        word.or(group)
+    })
+}
+
+/// The same grammar written as mutually recursive functions.
+fn lazy_group() -> Parser<Node> {
+    let node = Parser::lazy(|| {
+        let word = CharParser::char_if(char::is_alphanumeric)
+            .some()
+            .map(|xs| Node::Word(Text::from_iter(xs).to_string()));
+        word.or(lazy_group())
+    });
+    node.ignore(spaces())
+        .many()
+        .between2(CharParser::char('[').ignore(spaces()), CharParser::char(']'))
+        .map(|(_, children, _)| Node::Group(children))
+}
+
+fn main() {
+    let source = "[alpha [beta gamma] [[delta]] []]";
+    for parser in [grammar(), lazy_group()] {
+        match Parser::try_evaluate(source, parser) {
+            Ok(tree) => tree.print_pretty_tree(),
+            Err(error) => println!("{error}"),
+        }
+    }
+    match Parser::try_evaluate("[alpha [beta]", grammar()) {
+        Ok(tree) => tree.print_pretty_tree(),
+        Err(error) => println!("{error}"),
+    }
+}
@@ -0,0 +1,49 @@
+//! Times an emphasis grammar on unmatched `*****a` inputs, with and without
+//! `Parser::memoize`.
+//!
+//! ```text
+//! $ cargo run --release --example packrat-bench
+//! ```
+use std::time::{Duration, Instant};
+use markdown_parser::parser::*;
+
+/// ```text
+/// inline := "**" inline+ "**" | "*" inline+ "*" | [^*]
+/// ```
+fn grammar(memoize: bool) -> Parser<()> {
+    Parser::recursive(|inline| {
+        let strong = TextParser::token("**")
+            .keep(inline.clone().some())
+            .ignore(TextParser::token("**"))
+            .void();
+        let emphasis = CharParser::char('*')
+            .keep(inline.some())
+            .ignore(CharParser::char('*'))
+            .void();
+        let plain = CharParser::char_if(|x| x != '*').void();
+        let parser = Parser::options([strong, emphasis, plain]);
+        if memoize { parser.memoize() } else { parser }
+    })
+}
+
+fn time(source: &str, memoize: bool) -> Duration {
+    let parser = grammar(memoize).many();
+    let start = Instant::now();
+    let _ = Parser::evaluate(source, parser);
+    start.elapsed()
+}
+
+fn main() {
+    println!("{:>8} {:>14} {:>14}", "n", "plain", "memoized");
+    for n in [8, 12, 16, 20, 24] {
+        let source = format!("{}a", "*".repeat(n));
+        let plain = time(&source, false);
+        let memoized = time(&source, true);
+        println!("{n:>8} {:>14?} {:>14?}", plain, memoized);
+    }
+    for n in [500, 1_000, 2_000, 4_000] {
+        let source = format!("{}a", "*".repeat(n));
+        let memoized = time(&source, true);
+        println!("{n:>8} {:>14} {:>14?}", "-", memoized);
+    }
+}
@@ -0,0 +1,80 @@
+//! Times walking and slicing a large document with `Text`, against the
+//! previous representation: one `im_lists` node per character.
+//!
+//! ```text
+//! $ cargo run --release --example text-bench
+//!      bytes      walk list      walk text     slice list     slice text
+//!     100023     30.06325ms    23.012994ms    26.572255ms      285.868µs
+//!    1000076   349.162906ms   279.300343ms   322.243284ms     2.968021ms
+//!    4000073   1.558038943s   1.236910731s   1.377903788s    11.498324ms
+//! ```
+//!
+//! Walking one character at a time costs about the same either way, since
+//! `Text` looks up each character's position; slicing is about a hundred
+//! times faster.
+use std::time::{Duration, Instant};
+use im_lists::list::List;
+use markdown_parser::text::{FatChar, Text};
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn document(size: usize) -> String {
+    let paragraph = "Some *emphasis*, a [link](https://example.com) and `code`. Ünïcödé too.\n\n";
+    paragraph.repeat(size / paragraph.len() + 1)
+}
+
+/// Builds the document, then consumes it one character at a time.
+fn walk_text(source: &str) {
+    let mut text = Text::initialize_from(source);
+    while let Some((_, rest)) = text.uncons() {
+        text = rest;
+    }
+}
+
+/// Builds the document, then consumes it in 64 character slices.
+fn slice_text(source: &str) {
+    let mut text = Text::initialize_from(source);
+    while let Some((_, rest)) = text.take(64) {
+        text = rest;
+    }
+}
+
+fn list_of(source: &str) -> List<FatChar> {
+    Text::initialize_from(source).iter().collect()
+}
+
+fn walk_list(source: &str) {
+    let mut list = list_of(source);
+    while list.first().is_some() {
+        list = list.cdr().unwrap_or_default();
+    }
+}
+
+fn slice_list(source: &str) {
+    let mut list = list_of(source);
+    let mut length = list.len();
+    while length >= 64 {
+        let _ = list.take(64);
+        list = list.tail(64).unwrap_or_default();
+        length -= 64;
+    }
+}
+
+fn main() {
+    println!("{:>10} {:>14} {:>14} {:>14} {:>14}", "bytes", "walk list", "walk text", "slice list", "slice text");
+    for size in [100_000, 1_000_000, 4_000_000] {
+        let source = document(size);
+        println!(
+            "{:>10} {:>14?} {:>14?} {:>14?} {:>14?}",
+            source.len(),
+            time(|| walk_list(&source)),
+            time(|| walk_text(&source)),
+            time(|| slice_list(&source)),
+            time(|| slice_text(&source)),
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeSet;
+use itertools::Itertools;
+use tree_formatter::{PrettyTree, ToPrettyTree};
+use crate::text::PositionIndex;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// EXPECTED
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Something a parser was looking for when it failed.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Expected {
+    Char(char),
+    Token(String),
+    Label(String),
+    /// A regular expression, see `TextParser::regex` (the `regex` feature).
+    Pattern(String),
+    EndOfInput,
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Char(x) => write!(f, "{x:?}"),
+            Self::Token(x) => write!(f, "{x:?}"),
+            Self::Label(x) => write!(f, "{x}"),
+            Self::Pattern(x) => write!(f, "/{x}/"),
+            Self::EndOfInput => write!(f, "end of input"),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSE ERROR
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The furthest failure reached while evaluating a parser.
+///
+/// Failures at the same position are merged, so `expected` holds every
+/// alternative that was tried there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: PositionIndex,
+    /// The character found at `position`, or `None` at the end of input.
+    pub found: Option<char>,
+    pub expected: BTreeSet<Expected>,
+    /// Outermost frame first.
+    pub context: Vec<String>,
+}
+
+impl ParseError {
+    pub fn new(position: PositionIndex, found: Option<char>) -> Self {
+        Self {
+            position,
+            found,
+            expected: Default::default(),
+            context: Default::default(),
+        }
+    }
+    pub fn with_expected(mut self, expected: impl IntoIterator<Item = Expected>) -> Self {
+        self.expected.extend(expected);
+        self
+    }
+    pub fn with_context(mut self, context: impl IntoIterator<Item = String>) -> Self {
+        self.context = context.into_iter().collect();
+        self
+    }
+    /// Keeps whichever error got further, merging the two when they meet at the same offset.
+    pub fn merge(self, other: Self) -> Self {
+        let left = self.position.scalar_offset;
+        let right = other.position.scalar_offset;
+        match left.cmp(&right) {
+            std::cmp::Ordering::Greater => self,
+            std::cmp::Ordering::Less => other,
+            std::cmp::Ordering::Equal => {
+                let mut merged = self;
+                merged.expected.extend(other.expected);
+                if merged.context.len() < other.context.len() {
+                    merged.context = other.context;
+                }
+                merged
+            }
+        }
+    }
+}
+
+impl ParseError {
+    /// `unexpected 'x'` or `unexpected end of input`.
+    pub fn unexpected_message(&self) -> String {
+        match self.found {
+            Some(x) => format!("unexpected {x:?}"),
+            None => "unexpected end of input".to_owned(),
+        }
+    }
+    /// `expected 'x'` or `expected one of 'x', 'y'`, if anything was expected.
+    pub fn expected_message(&self) -> Option<String> {
+        match self.expected.len() {
+            0 => None,
+            1 => Some(format!("expected {}", self.expected.first().unwrap())),
+            _ => Some(format!("expected one of {}", self.expected.iter().join(", "))),
+        }
+    }
+    /// `while parsing outer > inner`, if there is any context.
+    pub fn context_message(&self) -> Option<String> {
+        if self.context.is_empty() {
+            return None
+        }
+        Some(format!("while parsing {}", self.context.join(" > ")))
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line = self.position.line_offset + 1;
+        let column = self.position.column_offset + 1;
+        write!(f, "{line}:{column}: {}", self.unexpected_message())?;
+        if let Some(expected) = self.expected_message() {
+            write!(f, ", {expected}")?;
+        }
+        if let Some(context) = self.context_message() {
+            write!(f, " ({context})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DIAGNOSTIC
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A failure that was recovered from, covering the input that was skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub start: PositionIndex,
+    pub end: PositionIndex,
+    pub error: ParseError,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DEBUG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl ToPrettyTree for Expected {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::value(self)
+    }
+}
+impl ToPrettyTree for ParseError {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        let line = self.position.line_offset + 1;
+        let column = self.position.column_offset + 1;
+        PrettyTree::branch_of("ParseError", vec![
+            PrettyTree::key_value("position", PrettyTree::value(format!("{line}:{column}"))),
+            PrettyTree::key_value("found", PrettyTree::value(format!("{:?}", self.found))),
+            PrettyTree::key_value("expected", PrettyTree::fragment(&self.expected)),
+            PrettyTree::key_value("context", PrettyTree::value(self.context.join(" > "))),
+        ])
+    }
+}
+impl ToPrettyTree for Diagnostic {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        let start = format!("{}:{}", self.start.line_offset + 1, self.start.column_offset + 1);
+        let end = format!("{}:{}", self.end.line_offset + 1, self.end.column_offset + 1);
+        PrettyTree::branch_of(format!("Diagnostic [{start}..{end}]"), [&self.error])
+    }
+}
@@ -0,0 +1,221 @@
+use crate::parser::{CharParser, Output, Parser, State, TextParser, UnitParser};
+use crate::shared::{Shareable, Shared};
+use crate::text::Text;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// OPERATOR TABLE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// Builds an operator-precedence (Pratt) parser over some atom parser.
+///
+/// Higher precedences bind tighter. After an operand, the longest matching
+/// postfix or infix operator is taken, so `!=` is never read as a postfix `!`;
+/// ties go to the first one added, postfix before infix. Prefix operators are
+/// tried in the order they were added, so list longer symbols first (`"--"`
+/// before `"-"`).
+/// Parenthesized sub-expressions and whitespace between tokens are handled
+/// by default, see [`OperatorTable::parens`] and [`OperatorTable::whitespace`].
+pub struct OperatorTable<T, U = ()> {
+    prefix: Vec<Prefix<T, U>>,
+    infix: Vec<Infix<T, U>>,
+    postfix: Vec<Postfix<T, U>>,
+    parens: Option<(TextParser<U>, TextParser<U>)>,
+    whitespace: UnitParser<U>,
+}
+
+struct Prefix<T, U> {
+    operator: TextParser<U>,
+    precedence: usize,
+    build: Box<dyn PrefixBuild<T>>,
+}
+
+struct Infix<T, U> {
+    operator: TextParser<U>,
+    precedence: usize,
+    associativity: Associativity,
+    build: Box<dyn InfixBuild<T>>,
+}
+
+struct Postfix<T, U> {
+    operator: TextParser<U>,
+    precedence: usize,
+    build: Box<dyn PostfixBuild<T>>,
+}
+
+trait PrefixBuild<T>: Fn(Text, T) -> T + Shareable {}
+trait InfixBuild<T>: Fn(T, Text, T) -> T + Shareable {}
+trait PostfixBuild<T>: Fn(T, Text) -> T + Shareable {}
+impl<T, F> PrefixBuild<T> for F where F: Fn(Text, T) -> T + Shareable {}
+impl<T, F> InfixBuild<T> for F where F: Fn(T, Text, T) -> T + Shareable {}
+impl<T, F> PostfixBuild<T> for F where F: Fn(T, Text) -> T + Shareable {}
+
+impl<T, U> Default for OperatorTable<T, U> where U: Clone + Shareable + 'static {
+    fn default() -> Self {
+        Self {
+            prefix: Vec::default(),
+            infix: Vec::default(),
+            postfix: Vec::default(),
+            parens: Some((TextParser::token("("), TextParser::token(")"))),
+            whitespace: CharParser::char_if(char::is_whitespace).many().void(),
+        }
+    }
+}
+
+impl<T, U> OperatorTable<T, U> where T: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    pub fn prefix(
+        mut self,
+        operator: TextParser<U>,
+        precedence: usize,
+        build: impl Fn(Text, T) -> T + Shareable + 'static,
+    ) -> Self {
+        self.prefix.push(Prefix { operator, precedence, build: Box::new(build) });
+        self
+    }
+    pub fn infix(
+        mut self,
+        operator: TextParser<U>,
+        precedence: usize,
+        associativity: Associativity,
+        build: impl Fn(T, Text, T) -> T + Shareable + 'static,
+    ) -> Self {
+        self.infix.push(Infix { operator, precedence, associativity, build: Box::new(build) });
+        self
+    }
+    pub fn postfix(
+        mut self,
+        operator: TextParser<U>,
+        precedence: usize,
+        build: impl Fn(T, Text) -> T + Shareable + 'static,
+    ) -> Self {
+        self.postfix.push(Postfix { operator, precedence, build: Box::new(build) });
+        self
+    }
+    /// Replaces the default `(` and `)` grouping tokens.
+    pub fn parens(mut self, open: TextParser<U>, close: TextParser<U>) -> Self {
+        self.parens = Some((open, close));
+        self
+    }
+    /// Disables grouping, e.g. when the atom parser already handles it.
+    pub fn without_parens(mut self) -> Self {
+        self.parens = None;
+        self
+    }
+    /// Replaces the default whitespace skipped between tokens.
+    pub fn whitespace(mut self, whitespace: UnitParser<U>) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+    /// The expression parser. Whitespace after the expression is not consumed.
+    pub fn build(self, atom: Parser<T, U>) -> Parser<T, U> {
+        let grammar = Shared::new(Grammar { table: self, atom });
+        Parser::i(move |state| grammar.expression(state, 0))
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// GRAMMAR
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+enum Operator<'a, T, U> {
+    Postfix(&'a Postfix<T, U>),
+    Infix(&'a Infix<T, U>),
+}
+
+struct Grammar<T, U> {
+    table: OperatorTable<T, U>,
+    atom: Parser<T, U>,
+}
+
+impl<T, U> Grammar<T, U> where T: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    /// Parses an expression whose operators all bind at least as tightly as `min_power`.
+    ///
+    /// Precedence `p` maps to the binding powers `2p` and `2p + 1`, the order of
+    /// which decides associativity.
+    fn expression(&self, state: State<U>, min_power: usize) -> Output<T, U> {
+        let Output::Ok { value: mut left, state: mut current } = self.operand(state.clone()) else {
+            return state.fail()
+        };
+        loop {
+            let next = self.skip_whitespace(current.clone());
+            let Some((operator, symbol, rest)) = self.longest_operator(next) else {
+                break
+            };
+            match operator {
+                Operator::Postfix(postfix) => {
+                    if postfix.precedence * 2 < min_power {
+                        break
+                    }
+                    left = (postfix.build)(left, symbol);
+                    current = rest;
+                }
+                Operator::Infix(infix) => {
+                    let (left_power, right_power) = match infix.associativity {
+                        Associativity::Left => (infix.precedence * 2, infix.precedence * 2 + 1),
+                        Associativity::Right => (infix.precedence * 2 + 1, infix.precedence * 2),
+                    };
+                    if left_power < min_power {
+                        break
+                    }
+                    let Output::Ok { value: right, state: rest } = self.expression(rest, right_power) else {
+                        return state.fail()
+                    };
+                    left = (infix.build)(left, symbol, right);
+                    current = rest;
+                }
+            }
+        }
+        current.ok(left)
+    }
+    /// The postfix or infix operator at `state` that consumes the most input.
+    fn longest_operator(&self, state: State<U>) -> Option<(Operator<'_, T, U>, Text, State<U>)> {
+        let postfix = self.table.postfix.iter().map(|x| (Operator::Postfix(x), &x.operator));
+        let infix = self.table.infix.iter().map(|x| (Operator::Infix(x), &x.operator));
+        let mut longest: Option<(Operator<'_, T, U>, Text, State<U>)> = None;
+        for (operator, parser) in postfix.chain(infix) {
+            let Output::Ok { value: symbol, state: rest } = (parser.binder)(state.clone()) else {
+                continue
+            };
+            let remaining = rest.text.byte_len();
+            if longest.as_ref().is_some_and(|(_, _, x)| x.text.byte_len() <= remaining) {
+                continue
+            }
+            longest = Some((operator, symbol, rest));
+        }
+        longest
+    }
+    /// A prefixed operand, a parenthesized expression or an atom.
+    fn operand(&self, state: State<U>) -> Output<T, U> {
+        let state = self.skip_whitespace(state);
+        for prefix in self.table.prefix.iter() {
+            let Output::Ok { value: operator, state: rest } = (prefix.operator.binder)(state.clone()) else {
+                continue
+            };
+            return match self.expression(rest, prefix.precedence * 2 + 1) {
+                Output::Ok { value, state } => state.ok((prefix.build)(operator, value)),
+                Output::Fail { .. } => state.fail(),
+            }
+        }
+        if let Some((open, close)) = self.table.parens.as_ref() {
+            if let Output::Ok { state: inner, .. } = (open.binder)(state.clone()) {
+                let Output::Ok { value, state: rest } = self.expression(inner, 0) else {
+                    return state.fail()
+                };
+                return match (close.binder)(self.skip_whitespace(rest)) {
+                    Output::Ok { state, .. } => state.ok(value),
+                    Output::Fail { .. } => state.fail(),
+                }
+            }
+        }
+        (self.atom.binder)(state)
+    }
+    fn skip_whitespace(&self, state: State<U>) -> State<U> {
+        match (self.table.whitespace.binder)(state.clone()) {
+            Output::Ok { state, .. } => state,
+            Output::Fail { .. } => state,
+        }
+    }
+}
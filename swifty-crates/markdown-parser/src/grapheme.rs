@@ -0,0 +1,79 @@
+//! Parsers over user-visible characters.
+//!
+//! `Text` is a sequence of scalars, so [`CharParser::next`] can split an
+//! emoji or a letter followed by a combining mark. These parsers consume
+//! whole grapheme clusters, using the [`PositionIndex::grapheme_offset`]
+//! every scalar of a cluster shares, and return each cluster as a [`Text`].
+//!
+//! [`CharParser::next`]: crate::parser::CharParser::next
+//! [`PositionIndex::grapheme_offset`]: crate::text::PositionIndex::grapheme_offset
+use crate::error::Expected;
+use crate::parser::TextParser;
+use crate::shared::Shareable;
+use crate::text::Text;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// GRAPHEME PARSERS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<U> TextParser<U> where U: Clone + Shareable + 'static {
+    /// The next grapheme cluster.
+    pub fn next_grapheme() -> Self {
+        Self::i(|state| {
+            match split_grapheme(&state.text) {
+                Some((grapheme, rest)) => state.set_text(rest).ok(grapheme),
+                None => state.unexpected(),
+            }
+        })
+    }
+    /// The next grapheme cluster, if it is exactly `value`.
+    pub fn grapheme(value: impl ToString) -> Self {
+        let value = value.to_string();
+        Self::i(move |state| {
+            match split_grapheme(&state.text) {
+                Some((grapheme, rest)) if grapheme.to_string() == value => {
+                    state.set_text(rest).ok(grapheme)
+                }
+                _ => state.expected(Expected::Token(value.clone())),
+            }
+        })
+    }
+    /// The next grapheme cluster, if it satisfies `predicate`.
+    pub fn grapheme_if(predicate: impl Fn(&str) -> bool + Shareable + 'static) -> Self {
+        Self::i(move |state| {
+            match split_grapheme(&state.text) {
+                Some((grapheme, rest)) if predicate(&grapheme.to_string()) => {
+                    state.set_text(rest).ok(grapheme)
+                }
+                _ => state.unexpected(),
+            }
+        })
+    }
+    /// Like [`TextParser::token`], but fails when `value` would end partway
+    /// through a grapheme cluster, e.g. `"e"` against `"e\u{301}"`.
+    pub fn grapheme_token(value: impl ToString) -> Self {
+        let value = value.to_string();
+        Self::i(move |state| {
+            match state.text.pop_prefix(&value) {
+                Some((prefix, rest)) if ends_on_boundary(&prefix, &rest) => state.set_text(rest).ok(prefix),
+                _ => state.expected(Expected::Token(value.clone())),
+            }
+        })
+    }
+}
+
+/// Splits the first grapheme cluster off `text`.
+fn split_grapheme(text: &Text) -> Option<(Text, Text)> {
+    let mut chars = text.iter();
+    let first = chars.next()?;
+    let count = 1 + chars
+        .take_while(|x| x.index.grapheme_offset == first.index.grapheme_offset)
+        .count();
+    text.take(count)
+}
+
+fn ends_on_boundary(prefix: &Text, rest: &Text) -> bool {
+    match (prefix.end(), rest.start()) {
+        (Some(last), Some(next)) => last.index.grapheme_offset != next.index.grapheme_offset,
+        _ => true,
+    }
+}
@@ -1,4 +1,16 @@
 #![allow(unused)]
 pub mod text;
+pub mod line_index;
 pub mod ast;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod error;
+pub mod trace;
+pub mod report;
+pub mod expression;
+pub mod parse;
+pub mod shared;
+pub mod prelude;
+pub mod grapheme;
+pub mod token;
+#[cfg(feature = "regex")]
+pub mod pattern;
\ No newline at end of file
@@ -0,0 +1,111 @@
+use unicode_segmentation::UnicodeSegmentation;
+use crate::text::PositionIndex;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// LINE INDEX
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Resolves byte offsets of a source string into full [`PositionIndex`]es.
+///
+/// Built once per source in a single pass. It keeps the start of every line,
+/// plus an anchor around each character that is not a lone ASCII byte; runs
+/// of ASCII in between are resolved arithmetically. Every lookup is a binary
+/// search, O(log n).
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// The byte offset each line starts at.
+    line_starts: Vec<usize>,
+    /// Sorted by byte offset.
+    anchors: Vec<Anchor>,
+    len: usize,
+}
+
+/// Offsets at a scalar boundary, valid up to the next anchor.
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    byte: usize,
+    scalar: usize,
+    grapheme: usize,
+    /// Set when the scalar at `byte` continues the previous grapheme.
+    continuation: bool,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut anchors = Vec::<Anchor>::new();
+        let mut scalar = 0;
+        for (grapheme, (byte, value)) in source.grapheme_indices(true).enumerate() {
+            let is_simple = value.len() == 1;
+            for (offset, char) in value.char_indices() {
+                if !is_simple {
+                    let byte = byte + offset;
+                    anchors.push(Anchor { byte, scalar, grapheme, continuation: offset > 0 });
+                }
+                scalar += 1;
+                if char == '\n' {
+                    line_starts.push(byte + offset + 1);
+                }
+            }
+            if !is_simple {
+                let byte = byte + value.len();
+                anchors.push(Anchor { byte, scalar, grapheme: grapheme + 1, continuation: false });
+            }
+        }
+        anchors.dedup_by_key(|x| x.byte);
+        Self { line_starts, anchors, len: source.len() }
+    }
+    /// The number of lines, counting the one after a trailing newline.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+    /// The byte offset `line` starts at.
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.line_starts.get(line).copied()
+    }
+    /// The zero-based line `byte` falls on.
+    pub fn line(&self, byte: usize) -> usize {
+        self.line_starts.partition_point(|x| *x <= byte) - 1
+    }
+    /// The zero-based column of `byte`, counted in graphemes.
+    ///
+    /// Scalars continuing a grapheme share its column.
+    pub fn column(&self, byte: usize) -> usize {
+        let line_start = self.line_starts[self.line(byte)];
+        self.grapheme_offset(byte) - self.grapheme_offset(line_start)
+    }
+    pub fn line_column(&self, byte: usize) -> (usize, usize) {
+        (self.line(byte), self.column(byte))
+    }
+    /// The number of scalars before `byte`.
+    pub fn scalar_offset(&self, byte: usize) -> usize {
+        match self.anchor(byte) {
+            Some(anchor) => anchor.scalar + (byte - anchor.byte),
+            None => byte,
+        }
+    }
+    /// The grapheme `byte` falls in, counted from the start of the source.
+    pub fn grapheme_offset(&self, byte: usize) -> usize {
+        match self.anchor(byte) {
+            Some(anchor) if anchor.continuation => anchor.grapheme,
+            Some(anchor) => anchor.grapheme + (byte - anchor.byte),
+            None => byte,
+        }
+    }
+    /// Every offset of `byte`, which must lie on a scalar boundary (or at the end).
+    pub fn position(&self, byte: usize) -> PositionIndex {
+        let byte = byte.min(self.len);
+        let (line_offset, column_offset) = self.line_column(byte);
+        PositionIndex {
+            grapheme_offset: self.grapheme_offset(byte),
+            scalar_offset: self.scalar_offset(byte),
+            byte_offset: byte,
+            line_offset,
+            column_offset,
+        }
+    }
+    /// The last anchor at or before `byte`.
+    fn anchor(&self, byte: usize) -> Option<&Anchor> {
+        let index = self.anchors.partition_point(|x| x.byte <= byte);
+        index.checked_sub(1).map(|x| &self.anchors[x])
+    }
+}
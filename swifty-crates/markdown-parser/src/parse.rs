@@ -0,0 +1,35 @@
+use crate::parser::{CharParser, Parser};
+use crate::shared::Shareable;
+use crate::text::FatChar;
+
+/// Re-exported so `#[derive(Parse)]` and the trait share one import.
+pub use markdown_parser_derive::Parse;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Types with a canonical parser, usually implemented with `#[derive(Parse)]`.
+///
+/// Structs parse their fields in order and enums try their variants in order;
+/// see the `markdown-parser-derive` crate for the supported attributes.
+pub trait Parse: Sized {
+    fn parser() -> Parser<Self>;
+}
+
+impl Parse for FatChar {
+    fn parser() -> Parser<Self> {
+        CharParser::next()
+    }
+}
+
+impl<T> Parse for Box<T> where T: Parse + Clone + Shareable + 'static {
+    fn parser() -> Parser<Self> {
+        Parser::lazy(T::parser).map(Box::new)
+    }
+}
+
+impl<T> Parse for Option<T> where T: Parse + Clone + Shareable + 'static {
+    fn parser() -> Parser<Self> {
+        Parser::lazy(T::parser).optional()
+    }
+}
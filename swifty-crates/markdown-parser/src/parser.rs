@@ -1,18 +1,29 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use colored::Colorize;
+use either::Either;
 use tree_formatter::{PrettyTree, PrettyTreePrinter, ToPrettyTree};
+use crate::error::{Diagnostic, Expected, ParseError};
 use crate::text::{FatChar, Text};
+use crate::shared::{Shareable, Shared, SharedCell, SharedWeak};
+use crate::trace::{Outcome, Trace, TraceRecorder};
 
-#[derive(Debug, Clone)]
+/// What a terminator tells a repetition to do before its next item.
+#[derive(Debug, Clone, Default)]
 pub enum ControlFlow {
+    /// Parse the next item.
+    #[default]
     NoOp,
+    /// Stop repeating.
     Terminate,
-}
-
-impl Default for ControlFlow {
-    fn default() -> Self {
-        ControlFlow::NoOp
-    }
+    /// Consume what the terminator matched and check again, without parsing
+    /// an item. Skipped input is left out of the result.
+    Skip,
+    /// Fail the whole repetition.
+    Fail,
 }
 
 impl ToPrettyTree for ControlFlow {
@@ -20,37 +31,215 @@ impl ToPrettyTree for ControlFlow {
         match self {
             ControlFlow::NoOp => PrettyTree::value("ControlFlow::NoOp"),
             ControlFlow::Terminate => PrettyTree::value("ControlFlow::Terminate"),
+            ControlFlow::Skip => PrettyTree::value("ControlFlow::Skip"),
+            ControlFlow::Fail => PrettyTree::value("ControlFlow::Fail"),
         }
     }
 }
 
+/// The result of [`Parser::lines`].
+#[derive(Debug, Clone)]
+pub struct Lines<Prefix, Content> {
+    /// The starting tokens of each line.
+    pub line_starts: Vec<Prefix>,
+    /// The parsed sub-content.
+    pub content: Content,
+}
+
+impl<Prefix, Content> ToPrettyTree for Lines<Prefix, Content> where Prefix: ToPrettyTree, Content: ToPrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::branch_of("Lines", vec![
+            PrettyTree::key_value("line_starts", PrettyTree::fragment(&self.line_starts)),
+            PrettyTree::key_value("content", &self.content),
+        ])
+    }
+}
+
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Clone)]
-pub struct Parser<T> {
-    pub(crate) binder: Rc<dyn Fn(State) -> Output<T>>
+/// A parser producing `T`, threading a user-defined state `U` (see [`State::user`]).
+pub struct Parser<T, U = ()> {
+    pub(crate) binder: Shared<dyn Binder<T, U>>
 }
 
-pub type TextParser = Parser<Text>;
-pub type CharParser = Parser<FatChar>;
-pub type TupleParser<A, B> = Parser<(A, B)>;
-pub type TripleParser<A, B, C> = Parser<(A, B, C)>;
-pub type QuadrupleParser<A, B, C, D> = Parser<(A, B, C, D)>;
-pub type ControlFlowParser = Parser<ControlFlow>;
+pub(crate) trait Binder<T, U>: Fn(State<U>) -> Output<T, U> + Shareable {}
+
+impl<T, U, F> Binder<T, U> for F where F: Fn(State<U>) -> Output<T, U> + Shareable {}
+
+impl<T, U> Clone for Parser<T, U> {
+    fn clone(&self) -> Self {
+        Self { binder: self.binder.clone() }
+    }
+}
+
+pub type TextParser<U = ()> = Parser<Text, U>;
+pub type CharParser<U = ()> = Parser<FatChar, U>;
+pub type TupleParser<A, B, U = ()> = Parser<(A, B), U>;
+pub type TripleParser<A, B, C, U = ()> = Parser<(A, B, C), U>;
+pub type QuadrupleParser<A, B, C, D, U = ()> = Parser<(A, B, C, D), U>;
+pub type ControlFlowParser<U = ()> = Parser<ControlFlow, U>;
+pub type UnitParser<U = ()> = Parser<(), U>;
+pub type EitherParser<A, B, U = ()> = Parser<Either<A, B>, U>;
 
 impl<T> Parser<T> {
     pub fn evaluate(source: impl AsRef<str>, parser: Self) -> (Option<T>, State) {
-        let snippet = State {
-            text: Text::initialize_from(source),
-        };
-        match (parser.binder)(snippet) {
+        Self::evaluate_with(source, (), parser)
+    }
+    /// Like [`Parser::evaluate`], but also returns the failures recovered from
+    /// along the way (see [`Parser::recover`]).
+    ///
+    /// If the parser still fails, its furthest error is appended as a final diagnostic.
+    pub fn evaluate_partial(source: impl AsRef<str>, parser: Self) -> (Option<T>, Vec<Diagnostic>) {
+        Self::evaluate_partial_with(source, (), parser)
+    }
+    /// Like [`Parser::evaluate`], but also records every [`Parser::traced`] attempt.
+    pub fn evaluate_traced(source: impl AsRef<str>, parser: Self) -> (Option<T>, State, Trace) {
+        Self::evaluate_traced_with(source, (), parser)
+    }
+    /// Like [`Parser::evaluate`], but reports the furthest failure on error.
+    pub fn try_evaluate(source: impl AsRef<str>, parser: Self) -> Result<T, ParseError> {
+        Self::try_evaluate_with(source, (), parser)
+    }
+}
+
+impl<T, U> Parser<T, U> {
+    /// Like [`Parser::evaluate`], starting from the given user state.
+    pub fn evaluate_with(source: impl AsRef<str>, user: U, parser: Self) -> (Option<T>, State<U>) {
+        let snippet = State::new(Text::initialize_from(source), user);
+        Self::run(snippet, parser)
+    }
+    /// Like [`Parser::evaluate_partial`], starting from the given user state.
+    pub fn evaluate_partial_with(source: impl AsRef<str>, user: U, parser: Self) -> (Option<T>, Vec<Diagnostic>) {
+        let (value, state) = Self::evaluate_with(source, user, parser);
+        let mut diagnostics = state.diagnostics();
+        if value.is_none() {
+            let error = state.error().unwrap_or_else(|| state.error_here());
+            diagnostics.push(Diagnostic { start: error.position, end: error.position, error });
+        }
+        (value, diagnostics)
+    }
+    /// Like [`Parser::evaluate_traced`], starting from the given user state.
+    pub fn evaluate_traced_with(source: impl AsRef<str>, user: U, parser: Self) -> (Option<T>, State<U>, Trace) {
+        let snippet = State::new(Text::initialize_from(source), user);
+        *snippet.session.trace.borrow_mut() = Some(TraceRecorder::default());
+        let (value, state) = Self::run(snippet, parser);
+        let trace = state.session.trace
+            .borrow_mut()
+            .take()
+            .map(TraceRecorder::finish)
+            .unwrap_or_default();
+        (value, state, trace)
+    }
+    /// Like [`Parser::try_evaluate`], starting from the given user state.
+    pub fn try_evaluate_with(source: impl AsRef<str>, user: U, parser: Self) -> Result<T, ParseError> {
+        match Self::evaluate_with(source, user, parser) {
+            (Some(value), _) => Ok(value),
+            (None, state) => Err(state.error().unwrap_or_else(|| state.error_here())),
+        }
+    }
+    pub(crate) fn i(f: impl Fn(State<U>) -> Output<T, U> + Shareable + 'static) -> Self {
+        Self { binder: Shared::new(f) }
+    }
+    fn run(snippet: State<U>, parser: Self) -> (Option<T>, State<U>) {
+        let (value, state) = match (parser.binder)(snippet) {
             Output::Ok { value, state } => (Some(value), state),
             Output::Fail { state } => (None, state)
-        }
+        };
+        state.session.memo.borrow_mut().clear();
+        (value, state)
+    }
+}
+
+impl<T, U> Parser<T, U> where T: 'static, U: Clone + Shareable + 'static {
+    /// Defers building the parser until it is first run, then reuses it.
+    ///
+    /// Useful for grammars defined by functions that refer to each other.
+    pub fn lazy(f: impl Fn() -> Self + Shareable + 'static) -> Self {
+        let cell = SharedCell::<Self>::new();
+        Self::i(move |state| {
+            let parser = cell.get_or_init(&f);
+            (parser.binder)(state)
+        })
+    }
+    /// Builds a self-referential parser, `f` receives a handle to the parser it returns.
+    ///
+    /// The handle only holds a weak reference, so no reference cycle is created; it
+    /// must not be run after the returned parser has been dropped.
+    pub fn recursive(f: impl FnOnce(Self) -> Self) -> Self {
+        let slot = Shared::new(SharedCell::<SharedWeak<dyn Binder<T, U>>>::new());
+        let handle = {
+            let slot = slot.clone();
+            Self::i(move |state| {
+                let binder = slot
+                    .get()
+                    .and_then(SharedWeak::upgrade)
+                    .expect("recursive parser run after it was dropped");
+                binder(state)
+            })
+        };
+        let parser = f(handle);
+        let _ = slot.set(Shared::downgrade(&parser.binder));
+        parser
+    }
+    /// Names this parser in error messages.
+    ///
+    /// When the parser fails without getting past its starting position, the
+    /// characters and tokens it expected are replaced with `label`.
+    pub fn label(self, label: impl ToString) -> Self {
+        let label = label.to_string();
+        Self::i(move |state| {
+            let saved = state.error();
+            let start = state.text.position().scalar_offset;
+            let output = (self.binder)(state.clone());
+            let furthest = state.error();
+            let progressed = furthest
+                .as_ref()
+                .is_some_and(|x| x.position.scalar_offset > start);
+            if furthest != saved && !progressed {
+                *state.session.furthest.borrow_mut() = saved;
+                state.report(Some(Expected::Label(label.clone())));
+            }
+            output
+        })
     }
-    pub(crate) fn i(f: impl Fn(State) -> Output<T> + 'static) -> Self {
-        Self { binder: Rc::new(f) }
+    /// Pushes a `name` frame onto the context stack while this parser runs, so
+    /// failures inside it read "while parsing outer > name".
+    pub fn context(self, name: impl ToString) -> Self {
+        let name = name.to_string();
+        Self::i(move |state| {
+            let outer = state.context.clone();
+            let mut inner = outer.clone();
+            inner.push_front(name.clone());
+            match (self.binder)(state.set_context(inner)) {
+                Output::Ok { value, state } => state.set_context(outer).ok(value),
+                Output::Fail { state } => state.set_context(outer).fail(),
+            }
+        })
+    }
+    /// Records each run of this parser under `label` when evaluated with
+    /// [`Parser::evaluate_traced`]; otherwise a no-op.
+    pub fn traced(self, label: impl ToString) -> Self {
+        let label = label.to_string();
+        Self::i(move |state| {
+            if state.session.trace.borrow().is_none() {
+                return (self.binder)(state)
+            }
+            let input = state.text.clone();
+            if let Some(trace) = state.session.trace.borrow_mut().as_mut() {
+                trace.enter(&label, input.position());
+            }
+            let session = state.session.clone();
+            let output = (self.binder)(state);
+            let (outcome, rest) = match &output {
+                Output::Ok { state, .. } => (Outcome::Ok, &state.text),
+                Output::Fail { state } => (Outcome::Fail, &state.text),
+            };
+            if let Some(trace) = session.trace.borrow_mut().as_mut() {
+                trace.exit(outcome, &input, rest);
+            }
+            output
+        })
     }
 }
 
@@ -58,23 +247,107 @@ impl<T> Parser<T> {
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 #[derive(Debug, Clone)]
-pub struct State {
+pub struct State<U = ()> {
     pub text: Text,
+    /// User-defined state, see [`Parser::user_state`] and friends.
+    pub user: U,
+    /// Innermost frame first.
+    pub(crate) context: im_lists::list::List<String>,
+    /// Recovered failures along the current path, most recent first.
+    pub(crate) diagnostics: im_lists::list::List<Diagnostic>,
+    /// Separates memoized results for text extracted by [`Parser::bounded`]
+    /// from those for the input it was extracted from.
+    pub(crate) memo_scope: usize,
+    pub(crate) session: Rc<Session>,
 }
 
-impl State {
-    pub(crate) fn ok<T>(self, value: T) -> Output<T> {
+impl<U> State<U> {
+    pub(crate) fn new(text: Text, user: U) -> Self {
+        Self {
+            text,
+            user,
+            context: Default::default(),
+            diagnostics: Default::default(),
+            memo_scope: 0,
+            session: Default::default(),
+        }
+    }
+    pub(crate) fn ok<T>(self, value: T) -> Output<T, U> {
         Output::Ok { value, state: self }
     }
-    pub(crate) fn fail<T>(self) -> Output<T> {
+    pub(crate) fn fail<T>(self) -> Output<T, U> {
         Output::Fail { state: self }
     }
+    /// Fails, recording that `expected` was wanted at the current position.
+    pub(crate) fn expected<T>(self, expected: Expected) -> Output<T, U> {
+        self.report(Some(expected));
+        self.fail()
+    }
+    /// Fails, recording the current position without saying what was wanted.
+    pub(crate) fn unexpected<T>(self) -> Output<T, U> {
+        self.report(None);
+        self.fail()
+    }
+    /// The failures recovered from along the path that led to this state, in input order.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = self.diagnostics.iter().cloned().collect::<Vec<_>>();
+        diagnostics.reverse();
+        diagnostics
+    }
+    /// The furthest failure recorded so far in this evaluation.
+    pub fn error(&self) -> Option<ParseError> {
+        self.session.furthest.borrow().clone()
+    }
+    pub(crate) fn error_here(&self) -> ParseError {
+        let found = self.text.start().map(|x| x.value);
+        let mut context = self.context.iter().cloned().collect::<Vec<_>>();
+        context.reverse();
+        ParseError::new(self.text.position(), found).with_context(context)
+    }
+    pub(crate) fn report(&self, expected: impl IntoIterator<Item = Expected>) {
+        let error = self.error_here().with_expected(expected);
+        let mut furthest = self.session.furthest.borrow_mut();
+        *furthest = Some(match furthest.take() {
+            Some(previous) => previous.merge(error),
+            None => error,
+        });
+    }
+}
+
+impl<U> State<U> where U: Clone {
     pub(crate) fn set_text(&self, text: Text) -> Self {
-        Self { text }
+        Self { text, ..self.clone() }
+    }
+    pub(crate) fn set_user(&self, user: U) -> Self {
+        Self { user, ..self.clone() }
+    }
+    pub(crate) fn set_context(&self, context: im_lists::list::List<String>) -> Self {
+        Self { context, ..self.clone() }
+    }
+    /// This state in a memo scope of its own.
+    pub(crate) fn new_memo_scope(&self) -> Self {
+        let memo_scope = self.session.memo_scopes.get() + 1;
+        self.session.memo_scopes.set(memo_scope);
+        Self { memo_scope, ..self.clone() }
+    }
+    pub(crate) fn set_memo_scope(&self, memo_scope: usize) -> Self {
+        Self { memo_scope, ..self.clone() }
+    }
+    pub(crate) fn push_diagnostic(&self, diagnostic: Diagnostic) -> Self {
+        let mut diagnostics = self.diagnostics.clone();
+        diagnostics.push_front(diagnostic);
+        Self { diagnostics, ..self.clone() }
+    }
+    /// Runs `f` without letting its failures reach the furthest error.
+    pub(crate) fn silently<T>(&self, f: impl FnOnce(Self) -> Output<T, U>) -> Output<T, U> {
+        let saved = self.session.furthest.borrow().clone();
+        let output = f(self.clone());
+        *self.session.furthest.borrow_mut() = saved;
+        output
     }
 }
 
-impl ToPrettyTree for State {
+impl<U> ToPrettyTree for State<U> {
     fn to_pretty_tree(&self) -> PrettyTree {
         PrettyTree::branch_of("State", vec![
             PrettyTree::key_value("text", &self.text)
@@ -82,47 +355,64 @@ impl ToPrettyTree for State {
     }
 }
 
+/// `(parser id, memo scope, scalar offset)`.
+pub(crate) type MemoKey = (usize, usize, usize);
+
+/// Bookkeeping shared by every `State` of a single evaluation.
+#[derive(Debug, Default)]
+pub(crate) struct Session {
+    pub(crate) furthest: RefCell<Option<ParseError>>,
+    /// Packrat results, see [`Parser::memoize`].
+    pub(crate) memo: RefCell<HashMap<MemoKey, Box<dyn Any>>>,
+    /// The last memo scope handed out.
+    pub(crate) memo_scopes: Cell<usize>,
+    /// Only present while running [`Parser::evaluate_traced`].
+    pub(crate) trace: RefCell<Option<TraceRecorder>>,
+}
+
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 #[derive(Debug, Clone)]
-pub(crate) enum Output<T> {
-    Ok { value: T, state: State},
-    Fail { state: State },
+pub(crate) enum Output<T, U> {
+    Ok { value: T, state: State<U> },
+    Fail { state: State<U> },
 }
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-impl<T> Parser<T> where T: Clone + 'static {
+impl<T, U> Parser<T, U> where T: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
     pub fn pure(value: T) -> Self {
         Self::i(move |s| s.ok(value.clone()))
     }
+    /// Always fails without consuming input.
+    pub fn fail() -> Self {
+        Self::i(|s| s.unexpected())
+    }
 }
 
-impl<A> Parser<A> where A: Clone + 'static {
-    pub fn and_then<B>(self, right: impl Fn(A) -> Parser<B> + 'static) -> Parser<B> where B: Clone + 'static {
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    pub fn and_then<B>(self, right: impl Fn(A) -> Parser<B, U> + Shareable + 'static) -> Parser<B, U> where B: Clone + Shareable + 'static {
         let left = self.binder.clone();
-        Parser::<B>::i(move |s1| {
+        Parser::<B, U>::i(move |s1| {
             match left(s1) {
                 Output::Ok { value: t, state: s2 } => (right(t).binder)(s2),
                 Output::Fail { state } => state.fail()
             }
         })
     }
-    pub fn map<B>(self, right: impl Fn(A) -> B + 'static) -> Parser<B> where B: Clone + 'static {
+    pub fn map<B>(self, right: impl Fn(A) -> B + Shareable + 'static) -> Parser<B, U> where B: Clone + Shareable + 'static {
         self.and_then(move |t| {
-            Parser::<B>::pure(right(t))
+            Parser::<B, U>::pure(right(t))
         })
     }
-    pub fn and<B>(self, next: Parser<B>) -> TupleParser<A, B> where B: 'static + Clone {
-        TupleParser::<A, B>::i(move |state| {
+    pub fn and<B>(self, next: Parser<B, U>) -> TupleParser<A, B, U> where B: Clone + Shareable + 'static {
+        TupleParser::<A, B, U>::i(move |state| {
             match (self.binder)(state) {
                 Output::Ok { value: t, state } => {
                     match (next.binder)(state) {
-                        Output::Ok { value: u, state } => {
-                            return state.ok((t, u))
-                        }
+                        Output::Ok { value: u, state } => state.ok((t, u)),
                         Output::Fail { state } => state.fail()
                     }
                 }
@@ -132,33 +422,268 @@ impl<A> Parser<A> where A: Clone + 'static {
     }
     pub fn and2<B, C>(
         self,
-        f: Parser<B>,
-        g: Parser<C>
-    ) -> TripleParser<A, B, C> where B: Clone + 'static, C: Clone + 'static {
+        f: Parser<B, U>,
+        g: Parser<C, U>
+    ) -> TripleParser<A, B, C, U> where B: Clone + Shareable + 'static, C: Clone + Shareable + 'static {
         self.and(f).and(g).map(|((a, b), c)| {
             (a, b, c)
         })
     }
     pub fn and3<B, C, D>(
         self,
-        f: Parser<B>,
-        g: Parser<C>,
-        h: Parser<D>
-    ) -> QuadrupleParser<A, B, C, D> where B: Clone + 'static, C: Clone + 'static, D: Clone + 'static {
+        f: Parser<B, U>,
+        g: Parser<C, U>,
+        h: Parser<D, U>
+    ) -> QuadrupleParser<A, B, C, D, U> where B: Clone + Shareable + 'static, C: Clone + Shareable + 'static, D: Clone + Shareable + 'static {
         self.and2(f, g).and(h).map(|((a, b, c), d)| {
             (a, b, c, d)
         })
     }
 }
 
-impl CharParser {
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    /// Tries each parser in order, returning the first success.
+    pub fn options(parsers: impl IntoIterator<Item = Self>) -> Self {
+        let parsers = parsers.into_iter().collect::<Vec<_>>();
+        Self::i(move |state| {
+            for parser in parsers.iter() {
+                if let Output::Ok { value, state } = (parser.binder)(state.clone()) {
+                    return state.ok(value)
+                }
+            }
+            state.fail()
+        })
+    }
+    pub fn or(self, other: Self) -> Self {
+        Self::options([self, other])
+    }
+    pub fn either<B>(self, other: Parser<B, U>) -> EitherParser<A, B, U> where B: Clone + Shareable + 'static {
+        EitherParser::<A, B, U>::i(move |state| {
+            if let Output::Ok { value, state } = (self.binder)(state.clone()) {
+                return state.ok(Either::Left(value))
+            }
+            match (other.binder)(state.clone()) {
+                Output::Ok { value, state } => state.ok(Either::Right(value)),
+                Output::Fail { .. } => state.fail(),
+            }
+        })
+    }
+    /// Succeeds with `None` instead of failing, without consuming input.
+    pub fn optional(self) -> Parser<Option<A>, U> {
+        Parser::<Option<A>, U>::i(move |state| {
+            match (self.binder)(state.clone()) {
+                Output::Ok { value, state } => state.ok(Some(value)),
+                Output::Fail { .. } => state.ok(None),
+            }
+        })
+    }
+    pub fn void(self) -> UnitParser<U> {
+        self.map(|_| ())
+    }
+    /// Runs `next` after this parser and keeps only the result of `next`.
+    pub fn keep<B>(self, next: Parser<B, U>) -> Parser<B, U> where B: Clone + Shareable + 'static {
+        self.and(next).map(|(_, b)| b)
+    }
+    /// Runs `next` after this parser and keeps only the result of this parser.
+    pub fn ignore<B>(self, next: Parser<B, U>) -> Parser<A, U> where B: Clone + Shareable + 'static {
+        self.and(next).map(|(a, _)| a)
+    }
+    pub fn between<B>(self, both_ends: Parser<B, U>) -> TripleParser<B, A, B, U> where B: Clone + Shareable + 'static {
+        both_ends.clone().and2(self, both_ends)
+    }
+    pub fn between2<B, C>(
+        self,
+        leading: Parser<B, U>,
+        trailing: Parser<C, U>
+    ) -> TripleParser<B, A, C, U> where B: Clone + Shareable + 'static, C: Clone + Shareable + 'static {
+        leading.and2(self, trailing)
+    }
+    /// Runs this parser with `text` pushed back onto the front of the input.
+    ///
+    /// The pushed-back input gets a memo scope of its own, since its positions
+    /// may repeat ones already cached.
+    pub fn put_back(self, text: Text) -> Self {
+        Self::i(move |state| {
+            let input = state.set_text(text.concat(&state.text)).new_memo_scope();
+            match (self.binder)(input) {
+                Output::Ok { value, state: rest } if rest.text.byte_len() <= state.text.byte_len() => {
+                    rest.set_memo_scope(state.memo_scope).ok(value)
+                }
+                Output::Ok { value, state } => state.ok(value),
+                Output::Fail { .. } => state.fail(),
+            }
+        })
+    }
+}
+
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    /// Runs this parser without consuming any input.
+    pub fn peek(self) -> Self {
+        Self::i(move |state| {
+            match (self.binder)(state.clone()) {
+                Output::Ok { value, .. } => state.ok(value),
+                Output::Fail { .. } => state.fail(),
+            }
+        })
+    }
+    /// Succeeds only if `next` matches after this parser; `next` is not consumed.
+    pub fn followed_by<B>(self, next: Parser<B, U>) -> Self where B: Clone + Shareable + 'static {
+        self.ignore(next.peek())
+    }
+    /// Succeeds only if `next` does not match after this parser; nothing past this parser is consumed.
+    pub fn not_followed_by<B>(self, next: Parser<B, U>) -> Self where B: Clone + Shareable + 'static {
+        Self::i(move |state| {
+            match (self.binder)(state) {
+                Output::Ok { value, state } => {
+                    match state.silently(|state| (next.binder)(state)) {
+                        Output::Ok { .. } => state.unexpected(),
+                        Output::Fail { .. } => state.ok(value),
+                    }
+                }
+                Output::Fail { state } => state.fail(),
+            }
+        })
+    }
+}
+
+/// A cached success: the value, the input and user state after it, and the
+/// diagnostics it recorded, newest first.
+type MemoEntry<A, U> = (A, Text, U, Vec<Diagnostic>);
+
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + PartialEq + Shareable + 'static {
+    /// Caches the outcome of this parser at each input position for the rest
+    /// of the evaluation, so backtracking into it again costs nothing.
+    ///
+    /// Clones of the returned parser share one cache entry per position. An
+    /// entry is only reused when the user state matches the one it was
+    /// computed with.
+    pub fn memoize(self) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        Self::i(move |state| {
+            let key = (id, state.memo_scope, state.text.position().scalar_offset);
+            let cached = state.session.memo
+                .borrow()
+                .get(&key)
+                .and_then(|x| x.downcast_ref::<(U, Option<MemoEntry<A, U>>)>())
+                .filter(|(user, _)| *user == state.user)
+                .map(|(_, entry)| entry.clone());
+            let entry = match cached {
+                Some(entry) => entry,
+                None => {
+                    let entry = match (self.binder)(state.clone()) {
+                        Output::Ok { value, state: rest } => {
+                            let added = rest.diagnostics.len().saturating_sub(state.diagnostics.len());
+                            let diagnostics = rest.diagnostics.iter().take(added).cloned().collect();
+                            Some((value, rest.text, rest.user, diagnostics))
+                        }
+                        Output::Fail { .. } => None,
+                    };
+                    let value = Box::new((state.user.clone(), entry.clone()));
+                    state.session.memo.borrow_mut().insert(key, value);
+                    entry
+                }
+            };
+            match entry {
+                Some((value, text, user, diagnostics)) => {
+                    let state = diagnostics
+                        .into_iter()
+                        .rev()
+                        .fold(state, |state, x| state.push_diagnostic(x));
+                    state.set_text(text).set_user(user).ok(value)
+                }
+                None => state.fail(),
+            }
+        })
+    }
+}
+
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    /// On failure, skips input up to and including the next `sync` match (or
+    /// to the end of input), records a [`Diagnostic`] for the skipped range and
+    /// succeeds with `error_node(skipped)`.
+    ///
+    /// Still fails if there is nothing left to skip.
+    pub fn recover<B>(self, sync: Parser<B, U>, error_node: impl Fn(Text) -> A + Shareable + 'static) -> Self where B: Clone + Shareable + 'static {
+        let skip = CharParser::next().many_unless(sync);
+        Self::i(move |state| {
+            let output = (self.binder)(state.clone());
+            let Output::Fail { .. } = output else {
+                return output
+            };
+            let start = state.text.position();
+            let error = state
+                .error()
+                .filter(|x| x.position.scalar_offset >= start.scalar_offset)
+                .unwrap_or_else(|| state.error_here());
+            let Output::Ok { state: rest, .. } = (skip.binder)(state.clone()) else {
+                return state.fail()
+            };
+            let skipped = state.text.consumed(&rest.text);
+            if skipped.is_empty() {
+                return state.fail()
+            }
+            let end = rest.text.position();
+            rest.push_diagnostic(Diagnostic { start, end, error }).ok(error_node(skipped))
+        })
+    }
+    /// [`Parser::recover`], resynchronizing after the next newline.
+    pub fn recover_to_next_line(self, error_node: impl Fn(Text) -> A + Shareable + 'static) -> Self {
+        self.recover(CharParser::char('\n'), error_node)
+    }
+    /// [`Parser::recover`], resynchronizing after the next blank line.
+    pub fn recover_to_blank_line(self, error_node: impl Fn(Text) -> A + Shareable + 'static) -> Self {
+        let blank_line = CharParser::char('\n')
+            .and(CharParser::char_if(|x| x == ' ' || x == '\t').many())
+            .and(CharParser::char('\n').void().or(UnitParser::end_of_input()));
+        self.recover(blank_line, error_node)
+    }
+}
+
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    /// Runs this parser with the user state replaced by `f(&user)`, then
+    /// restores the previous user state.
+    pub fn with_user_state(self, f: impl Fn(&U) -> U + Shareable + 'static) -> Self {
+        Self::i(move |state| {
+            let outer = state.user.clone();
+            match (self.binder)(state.set_user(f(&outer))) {
+                Output::Ok { value, state } => state.set_user(outer).ok(value),
+                Output::Fail { state } => state.set_user(outer).fail(),
+            }
+        })
+    }
+}
+
+impl<U> Parser<U, U> where U: Clone + Shareable + 'static {
+    /// Returns the current user state without consuming input.
+    pub fn user_state() -> Self {
+        Self::i(|state| {
+            let user = state.user.clone();
+            state.ok(user)
+        })
+    }
+}
+
+impl<U> UnitParser<U> where U: Clone + Shareable + 'static {
+    pub fn set_user_state(user: U) -> Self {
+        Self::i(move |state| state.set_user(user.clone()).ok(()))
+    }
+    pub fn update_user_state(f: impl Fn(&U) -> U + Shareable + 'static) -> Self {
+        Self::i(move |state| {
+            let user = f(&state.user);
+            state.set_user(user).ok(())
+        })
+    }
+}
+
+impl<U> CharParser<U> where U: Clone + Shareable + 'static {
     pub fn next() -> Self {
         Self::i(|state| {
             match state.text.uncons() {
                 Some((l, r)) => {
                     state.set_text(r).ok(l)
                 }
-                None => state.fail()
+                None => state.unexpected()
             }
         })
     }
@@ -168,104 +693,446 @@ impl CharParser {
                 Some((l, r)) if l.value == value => {
                     state.set_text(r).ok(l)
                 }
-                _ => state.fail()
+                _ => state.expected(Expected::Char(value))
             }
         })
     }
-    pub fn char_if(predicate: impl Fn(char) -> bool + 'static) -> Self {
+    pub fn char_if(predicate: impl Fn(char) -> bool + Shareable + 'static) -> Self {
         Self::i(move |state| {
             match state.text.uncons() {
                 Some((l, r)) if predicate(l.value) => {
                     state.set_text(r).ok(l)
                 }
-                _ => state.fail()
+                _ => state.unexpected()
             }
         })
     }
 }
 
-impl TextParser {
+impl<U> TextParser<U> where U: Clone + Shareable + 'static {
     pub fn token(value: impl ToString) -> Self {
         let value = value.to_string();
         Self::i(move |state| {
             match state.text.pop_prefix(&value) {
                 Some((prefix, rest)) => state.set_text(rest).ok(prefix),
-                None => state.fail()
+                None => state.expected(Expected::Token(value.clone()))
             }
         })
     }
+    /// Consumes the block starting at the current character: every following
+    /// character that is whitespace or sits at or right of its column.
+    ///
+    /// With `deindent`, characters left of that column are dropped from the
+    /// result (newlines are kept). Trailing whitespace is left in the input.
+    pub fn whole_indented_block(deindent: bool) -> Self {
+        Self::i(move |state| {
+            let Some(column) = state.text.start().map(|x| x.index.column_offset) else {
+                let empty = state.text.clone();
+                return state.ok(empty)
+            };
+            let is_indented = move |x: &FatChar| x.value == '\n' || x.index.column_offset >= column;
+            let count = state.text
+                .iter()
+                .take_while(|x| x.value.is_whitespace() || is_indented(x))
+                .count();
+            let (block, rest) = state.text.take(count).unwrap();
+            let block = match deindent {
+                true => Text::from_iter(block.iter().filter(is_indented)),
+                false => block,
+            };
+            let (block, trailing) = block.trim_trailing(true);
+            state.set_text(trailing.concat(&rest)).ok(block)
+        })
+    }
 }
 
-#[derive(Default)]
-pub struct SequenceSettings {
-    allow_empty: Option<bool>,
-    until_terminator: Option<ControlFlowParser>,
+impl<U> UnitParser<U> where U: Clone + Shareable + 'static {
+    pub fn unit() -> Self {
+        Self::pure(())
+    }
+    /// Succeeds only when there is no input left.
+    pub fn end_of_input() -> Self {
+        Self::i(|state| {
+            if state.text.is_empty() {
+                return state.ok(())
+            }
+            state.expected(Expected::EndOfInput)
+        })
+    }
+    /// Consumes consecutive lines that each begin with `line_start`, such as
+    /// the `>` markers of a blockquote.
+    ///
+    /// Every prefix must end at the same column as the first one, and
+    /// `terminator` is checked before each line; lines it skips are left out
+    /// of the content. With `trim` the prefixes are
+    /// stripped from the content; either way the content keeps its original
+    /// positions. Trailing whitespace is left in the input. Fails unless at
+    /// least one line matches.
+    pub fn lines(
+        line_start: TextParser<U>,
+        terminator: ControlFlowParser<U>,
+        trim: bool,
+    ) -> Parser<Lines<Text, Text>, U> {
+        let remaining_line = CharParser::char_if(|x| x != '\n')
+            .many()
+            .and(CharParser::char('\n').optional())
+            .map(|(mut chars, newline)| {
+                chars.extend(newline);
+                Text::from_iter(chars)
+            });
+        let line = line_start.and(remaining_line);
+        Parser::<Lines<Text, Text>, U>::i(move |state| {
+            let mut current = state.clone();
+            let mut end = state.clone();
+            let mut terminated = None;
+            let mut line_starts = Vec::<Text>::new();
+            let mut content = Vec::<FatChar>::new();
+            let mut column: Option<usize> = None;
+            while !current.text.is_empty() {
+                match (terminator.binder)(current.clone()) {
+                    Output::Ok { value: ControlFlow::Terminate, state } => {
+                        // Skipped input is only consumed when another line follows it.
+                        if current.text.same_cursor(&end.text) {
+                            terminated = Some(state);
+                        }
+                        break
+                    }
+                    Output::Ok { value: ControlFlow::Skip, state } if !state.text.same_cursor(&current.text) => {
+                        current = state;
+                        continue
+                    }
+                    Output::Ok { value: ControlFlow::Fail, .. } => return current.unexpected(),
+                    _ => (),
+                }
+                let Output::Ok { value: (prefix, rest), state } = (line.binder)(current.clone()) else {
+                    break
+                };
+                let Some(last) = prefix.end() else {
+                    break
+                };
+                if *column.get_or_insert(last.index.column_offset) != last.index.column_offset {
+                    break
+                }
+                if !trim {
+                    content.extend(prefix.iter());
+                }
+                content.extend(rest.iter());
+                line_starts.push(prefix);
+                current = state;
+                end = current.clone();
+            }
+            if line_starts.is_empty() {
+                return state.fail()
+            }
+            let (content, trailing) = match content.is_empty() {
+                true => {
+                    let (empty, _) = end.text.split_at_byte(0);
+                    (empty.clone(), empty)
+                }
+                false => Text::from_iter(content).trim_trailing(true),
+            };
+            match terminated {
+                // A consuming terminator takes the trailing whitespace with it.
+                Some(after) if !after.text.same_cursor(&end.text) => {
+                    after.ok(Lines { line_starts, content })
+                }
+                _ => {
+                    let rest = trailing.concat(&end.text);
+                    end.set_text(rest).ok(Lines { line_starts, content })
+                }
+            }
+        })
+    }
+    /// Runs `execute` over the text produced by `extract`, e.g. the content of
+    /// a blockquote with its markers stripped.
+    ///
+    /// If `execute` stops short of the extracted text, parsing resumes from the
+    /// corresponding point in the original input; otherwise it resumes after
+    /// the extracted region. Positions always refer to the original document.
+    pub fn bounded<T>(extract: TextParser<U>, execute: Parser<T, U>) -> Parser<T, U> where T: Clone + Shareable + 'static {
+        Parser::<T, U>::i(move |state| {
+            let Output::Ok { value: region, state: trailing } = (extract.binder)(state.clone()) else {
+                return state.fail()
+            };
+            let inner = trailing.set_text(region).new_memo_scope();
+            let Output::Ok { value, state: rest } = (execute.binder)(inner) else {
+                return state.fail()
+            };
+            let rest = rest.set_memo_scope(state.memo_scope);
+            let text = match rest.text.start_index() {
+                Some(position) => state.text.seek(position).unwrap_or(trailing.text),
+                None => trailing.text,
+            };
+            rest.set_text(text).ok(value)
+        })
+    }
+    /// Runs `execute` over `text` instead of the current input.
+    ///
+    /// The current input is left as is, unless `execute` stops at a point that
+    /// lies within it, in which case parsing resumes from there.
+    pub fn bounded_fork<T>(text: Text, execute: Parser<T, U>) -> Parser<T, U> where T: Clone + Shareable + 'static {
+        Parser::<T, U>::i(move |state| {
+            let inner = state.set_text(text.clone()).new_memo_scope();
+            let Output::Ok { value, state: rest } = (execute.binder)(inner) else {
+                return state.fail()
+            };
+            let rest = rest.set_memo_scope(state.memo_scope);
+            let text = rest.text
+                .start_index()
+                .and_then(|position| state.text.seek(position))
+                .unwrap_or_else(|| state.text.clone());
+            rest.set_text(text).ok(value)
+        })
+    }
+}
+
+pub struct SequenceSettings<U = ()> {
+    min: usize,
+    max: Option<usize>,
+    separator: Option<UnitParser<U>>,
+    allow_trailing: bool,
+    until_terminator: Option<ControlFlowParser<U>>,
+    terminator_policy: TerminatorPolicy,
+}
+
+/// What [`Parser::sequence`] does with the input a terminator matched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TerminatorPolicy {
+    /// Consume it.
+    #[default]
+    Consume,
+    /// Leave it in the input.
+    Leave,
+    /// Consume it, and return its text from [`Parser::sequence_with_terminator`].
+    /// [`Parser::sequence`] drops it, as with `Consume`.
+    Return,
+}
+
+impl<U> Default for SequenceSettings<U> {
+    fn default() -> Self {
+        Self {
+            min: 1,
+            max: None,
+            separator: None,
+            allow_trailing: false,
+            until_terminator: None,
+            terminator_policy: TerminatorPolicy::default(),
+        }
+    }
 }
 
-impl SequenceSettings {
-    pub fn allow_empty(mut self, flag: bool) -> Self {
-        self.allow_empty = Some(flag);
+impl<U> SequenceSettings<U> {
+    /// Shorthand for `min(0)` or `min(1)`.
+    pub fn allow_empty(self, flag: bool) -> Self {
+        self.min(!flag as usize)
+    }
+    /// Fails unless at least `count` items are parsed; one by default.
+    pub fn min(mut self, count: usize) -> Self {
+        self.min = count;
+        self
+    }
+    /// Stops after `count` items.
+    pub fn max(mut self, count: usize) -> Self {
+        self.max = Some(count);
+        self
+    }
+    /// Requires `separator` between items. A separator not followed by an
+    /// item is left in the input, unless trailing separators are allowed.
+    pub fn separator(mut self, separator: UnitParser<U>) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+    /// Consumes a separator after the last item, if there is one.
+    pub fn allow_trailing(mut self, flag: bool) -> Self {
+        self.allow_trailing = flag;
         self
     }
-    pub fn until_terminator(mut self, terminator: ControlFlowParser) -> Self {
+    pub fn until_terminator(mut self, terminator: ControlFlowParser<U>) -> Self {
         self.until_terminator = Some(terminator);
         self
     }
+    pub fn terminator_policy(mut self, policy: TerminatorPolicy) -> Self {
+        self.terminator_policy = policy;
+        self
+    }
 }
 
-impl<A> Parser<A> where A: Clone + 'static {
-    pub fn sequence(self, settings: SequenceSettings) -> Parser<Vec<A>> {
-        Parser::<Vec<A>>::i(move |state| {
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    /// Repeats this parser as `settings` allow.
+    ///
+    /// An item that succeeds without consuming input ends the sequence, and is
+    /// not included in it, unless a separator comes before or after it.
+    pub fn sequence(self, settings: SequenceSettings<U>) -> Parser<Vec<A>, U> {
+        self.sequence_with_terminator(settings).map(|(items, _)| items)
+    }
+    /// Like [`Parser::sequence`], also returning the text the terminator
+    /// matched when it stopped the sequence under [`TerminatorPolicy::Return`].
+    pub fn sequence_with_terminator(self, settings: SequenceSettings<U>) -> TupleParser<Vec<A>, Option<Text>, U> {
+        Parser::<(Vec<A>, Option<Text>), U>::i(move |state| {
             let mut leading = Vec::<A>::default();
-            let mut trailing: State = state.clone();
-            'trials : while !trailing.text.is_empty() {
+            let mut trailing: State<U> = state.clone();
+            let mut next: State<U> = state.clone();
+            let mut terminator_text = None;
+            let mut pending_separator = false;
+            // Where the last item ended. Progress is measured from here, so an
+            // empty item still counts when a separator comes before or after it.
+            let mut item_end = state.text.clone();
+            let separator_follows = |state: &State<U>| {
+                let Some(separator) = settings.separator.as_ref() else {
+                    return false
+                };
+                match (separator.binder)(state.clone()) {
+                    Output::Ok { state: rest, .. } => !rest.text.same_cursor(&state.text),
+                    Output::Fail { .. } => false,
+                }
+            };
+            'trials : while !next.text.is_empty() {
+                if settings.max.is_some_and(|max| leading.len() >= max) {
+                    break 'trials;
+                }
                 if let Some(terminator) = settings.until_terminator.as_ref() {
-                    match (terminator.binder.as_ref())(trailing.clone()) {
+                    match (terminator.binder.as_ref())(next.clone()) {
+                        Output::Ok { value: ControlFlow::Terminate, .. } if pending_separator => break 'trials,
                         Output::Ok { value: ControlFlow::Terminate, state } => {
-                            trailing = state;
+                            trailing = match settings.terminator_policy {
+                                TerminatorPolicy::Consume => state,
+                                TerminatorPolicy::Leave => next,
+                                TerminatorPolicy::Return => {
+                                    terminator_text = Some(next.text.consumed(&state.text));
+                                    state
+                                }
+                            };
                             break 'trials;
                         }
-                        _ => ()
+                        Output::Ok { value: ControlFlow::Skip, state } if !state.text.same_cursor(&next.text) => {
+                            if !pending_separator {
+                                trailing = state.clone();
+                            }
+                            next = state;
+                            continue 'trials;
+                        }
+                        Output::Ok { value: ControlFlow::Fail, .. } => return next.unexpected(),
+                        _ => (),
                     }
                 }
-                match (self.binder.as_ref())(trailing.clone()) {
+                match (self.binder.as_ref())(next.clone()) {
+                    Output::Ok { state, .. } if state.text.same_cursor(&item_end) && !separator_follows(&state) => {
+                        break 'trials
+                    }
                     Output::Ok { value, state } => {
-                        trailing = state;
+                        item_end = state.text.clone();
+                        trailing = state.clone();
+                        next = state;
                         leading.push(value);
-                        continue 'trials;
+                        pending_separator = false;
                     }
                     Output::Fail { .. } => break 'trials,
                 }
+                if let Some(separator) = settings.separator.as_ref() {
+                    match (separator.binder.as_ref())(next.clone()) {
+                        Output::Ok { state, .. } if settings.allow_trailing => {
+                            trailing = state.clone();
+                            next = state;
+                        }
+                        Output::Ok { state, .. } => {
+                            next = state;
+                            pending_separator = true;
+                        }
+                        Output::Fail { .. } => break 'trials,
+                    }
+                }
             }
-            if leading.is_empty() && !settings.allow_empty.unwrap_or(false) {
-                return trailing.fail()
+            if leading.len() < settings.min {
+                return trailing.unexpected()
             }
-            trailing.ok(leading)
+            trailing.ok((leading, terminator_text))
         })
     }
-    pub fn many(self) -> Parser<Vec<A>> {
+    pub fn many(self) -> Parser<Vec<A>, U> {
         let settings = SequenceSettings::default().allow_empty(true);
         self.sequence(settings)
     }
-    pub fn some(self) -> Parser<Vec<A>> {
+    pub fn some(self) -> Parser<Vec<A>, U> {
         let settings = SequenceSettings::default().allow_empty(false);
         self.sequence(settings)
     }
-    pub fn many_unless<B>(self, other: Parser<B>) -> Parser<(Vec<A>, Option<B>)> where B: 'static + Clone {
+    /// Zero or more items, stopping as soon as `terminator` would match; the
+    /// terminator is then consumed if present.
+    pub fn many_unless<B>(self, terminator: Parser<B, U>) -> TupleParser<Vec<A>, Option<B>, U> where B: Clone + Shareable + 'static {
+        self.sequence_until(true, &terminator).and(terminator.optional())
+    }
+    /// Like [`Parser::many_unless`], but fails unless at least one item is parsed.
+    pub fn some_unless<B>(self, terminator: Parser<B, U>) -> TupleParser<Vec<A>, Option<B>, U> where B: Clone + Shareable + 'static {
+        self.sequence_until(false, &terminator).and(terminator.optional())
+    }
+    /// Zero or more items followed by a mandatory `terminator`, which is consumed.
+    pub fn many_till<B>(self, terminator: Parser<B, U>) -> TupleParser<Vec<A>, B, U> where B: Clone + Shareable + 'static {
+        self.sequence_until(true, &terminator).and(terminator)
+    }
+    /// Like [`Parser::many_till`], but fails unless at least one item is parsed.
+    pub fn some_till<B>(self, terminator: Parser<B, U>) -> TupleParser<Vec<A>, B, U> where B: Clone + Shareable + 'static {
+        self.sequence_until(false, &terminator).and(terminator)
+    }
+    fn sequence_until<B>(self, allow_empty: bool, terminator: &Parser<B, U>) -> Parser<Vec<A>, U> where B: Clone + Shareable + 'static {
         let settings = SequenceSettings::default()
-            .allow_empty(true)
-            .until_terminator(ControlFlowParser::terminate_if_ok(&other));
-        let parser = self.and(other);
-        unimplemented!()
+            .allow_empty(allow_empty)
+            .until_terminator(ControlFlowParser::terminate_if_ok(terminator));
+        self.sequence(settings)
+    }
+}
+
+impl<A, U> Parser<A, U> where A: Clone + Shareable + 'static, U: Clone + Shareable + 'static {
+    /// Zero or more items separated by `separator`.
+    pub fn sep_by<S>(self, separator: Parser<S, U>) -> Parser<Vec<A>, U> where S: Clone + Shareable + 'static {
+        self.sequence(SequenceSettings::default().min(0).separator(separator.void()))
+    }
+    /// One or more items separated by `separator`.
+    pub fn sep_by1<S>(self, separator: Parser<S, U>) -> Parser<Vec<A>, U> where S: Clone + Shareable + 'static {
+        self.sequence(SequenceSettings::default().separator(separator.void()))
+    }
+    /// Like [`Parser::sep_by`], also consuming a separator after the last item.
+    pub fn sep_end_by<S>(self, separator: Parser<S, U>) -> Parser<Vec<A>, U> where S: Clone + Shareable + 'static {
+        self.sequence(SequenceSettings::default().min(0).separator(separator.void()).allow_trailing(true))
+    }
+    /// Like [`Parser::sep_by1`], also consuming a separator after the last item.
+    pub fn sep_end_by1<S>(self, separator: Parser<S, U>) -> Parser<Vec<A>, U> where S: Clone + Shareable + 'static {
+        self.sequence(SequenceSettings::default().separator(separator.void()).allow_trailing(true))
+    }
+    /// One or more items joined by `operator`, folded from the left:
+    /// `a - b - c` is `fold(fold(a, -, b), -, c)`.
+    pub fn chainl1<O>(self, operator: Parser<O, U>, fold: impl Fn(A, O, A) -> A + Shareable + 'static) -> Self where O: Clone + Shareable + 'static {
+        self.clone()
+            .and(operator.and(self).many())
+            .map(move |(first, rest)| {
+                rest.into_iter().fold(first, |left, (operator, right)| fold(left, operator, right))
+            })
+    }
+    /// One or more items joined by `operator`, folded from the right:
+    /// `a ^ b ^ c` is `fold(a, ^, fold(b, ^, c))`.
+    pub fn chainr1<O>(self, operator: Parser<O, U>, fold: impl Fn(A, O, A) -> A + Shareable + 'static) -> Self where O: Clone + Shareable + 'static {
+        self.clone()
+            .and(operator.and(self).many())
+            .map(move |(first, rest)| {
+                let (operators, mut operands): (Vec<O>, Vec<A>) = rest.into_iter().unzip();
+                operands.insert(0, first);
+                let last = operands.pop().unwrap();
+                operators
+                    .into_iter()
+                    .zip(operands)
+                    .rev()
+                    .fold(last, |right, (operator, left)| fold(left, operator, right))
+            })
     }
-    // pub fn some_unless(self) -> Parser<Vec<T>> {
-    //     let settings = SequenceSettings::default().allow_empty(false);
-    //     self.sequence(settings)
-    // }
 }
 
-impl ControlFlowParser {
-    pub fn terminate_if_ok<T>(parser: &Parser<T>) -> Self where T: 'static + Clone {
+impl<U> ControlFlowParser<U> where U: Clone + Shareable + 'static {
+    /// Never terminates.
+    pub fn noop() -> Self {
+        Self::pure(ControlFlow::NoOp)
+    }
+    /// Terminates where `parser` would match, without consuming anything, so
+    /// every [`TerminatorPolicy`] leaves the match in the input.
+    pub fn terminate_if_ok<T>(parser: &Parser<T, U>) -> Self where T: Clone + Shareable + 'static {
         let parser = parser.to_owned();
         Self::i(move |state| {
             match (parser.binder)(state.clone()) {
@@ -274,6 +1141,27 @@ impl ControlFlowParser {
             }
         })
     }
+    /// Terminates where `parser` matches, consuming the match; the
+    /// [`TerminatorPolicy`] decides whether it stays consumed.
+    pub fn terminate_on<T>(parser: Parser<T, U>) -> Self where T: Clone + Shareable + 'static {
+        Self::on(parser, ControlFlow::Terminate)
+    }
+    /// Skips whatever `parser` matches.
+    pub fn skip_on<T>(parser: Parser<T, U>) -> Self where T: Clone + Shareable + 'static {
+        Self::on(parser, ControlFlow::Skip)
+    }
+    /// Fails the repetition where `parser` matches.
+    pub fn fail_on<T>(parser: Parser<T, U>) -> Self where T: Clone + Shareable + 'static {
+        Self::on(parser, ControlFlow::Fail)
+    }
+    fn on<T>(parser: Parser<T, U>, flow: ControlFlow) -> Self where T: Clone + Shareable + 'static {
+        Self::i(move |state| {
+            match (parser.binder)(state.clone()) {
+                Output::Ok { state, .. } => state.ok(flow.clone()),
+                Output::Fail { .. } => state.ok(ControlFlow::NoOp),
+            }
+        })
+    }
 }
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
@@ -282,15 +1170,17 @@ impl ControlFlowParser {
 pub fn dev() {
     let source = "Hello World";
     let parser = {
-        let is_whitespace = CharParser::char_if(|x| x.is_whitespace());
+        let is_whitespace = CharParser::char_if(|x| x.is_whitespace()).traced("whitespace");
         let settings = SequenceSettings::default()
             .allow_empty(false)
             .until_terminator(ControlFlowParser::terminate_if_ok(&is_whitespace));
         CharParser::next()
+            .traced("next")
             .sequence(settings)
             .map(Text::from_iter)
+            .traced("word")
     };
-    let (output, state) = Parser::evaluate(source, parser);
+    let (output, state, trace) = Parser::evaluate_traced(source, parser);
     if let Some(output) = output {
         header("DONE");
         output.to_pretty_tree().print_pretty_tree();
@@ -299,6 +1189,8 @@ pub fn dev() {
     }
     header("FINAL PARSER STATE");
     state.to_pretty_tree().print_pretty_tree();
+    header("TRACE");
+    trace.print_pretty_tree();
 }
 
 pub fn header(value: impl AsRef<str>) {
@@ -309,9 +1201,9 @@ pub fn header(value: impl AsRef<str>) {
         let top_border    = format!("╭{}╮", "─".repeat(length + 2));
         let bottom_border = format!("╰{}╯", "─".repeat(length + 2));
         // - -
-        let line1 = format!("{}", top_border).cyan();
+        let line1 = top_border.cyan();
         let line2 = format!("│ {} │", label).cyan();
-        let line3 = format!("{}", bottom_border).cyan();
+        let line3 = bottom_border.cyan();
         // - -
         println!("{line1}");
         println!("{line2}");
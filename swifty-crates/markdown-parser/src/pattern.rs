@@ -0,0 +1,62 @@
+//! Regex-backed text parsers, behind the `regex` feature.
+use std::collections::HashMap;
+use regex::Regex;
+use crate::error::Expected;
+use crate::parser::{Parser, TextParser};
+use crate::shared::Shareable;
+use crate::text::Text;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// CAPTURES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The result of [`TextParser::regex_captures`].
+#[derive(Debug, Clone)]
+pub struct Captures {
+    /// The whole match.
+    pub text: Text,
+    /// Every named group that took part in the match.
+    pub named: HashMap<String, Text>,
+}
+
+impl Captures {
+    pub fn get(&self, name: &str) -> Option<&Text> {
+        self.named.get(name)
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSERS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<U> TextParser<U> where U: Clone + Shareable + 'static {
+    /// Matches `pattern` at the current position, never further along.
+    ///
+    /// Panics if `pattern` is not a valid regular expression.
+    pub fn regex(pattern: impl AsRef<str>) -> Self {
+        Parser::regex_captures(pattern).map(|x| x.text)
+    }
+    /// Like [`TextParser::regex`], also returning the named groups of the match.
+    ///
+    /// Captured text keeps its original positions.
+    pub fn regex_captures(pattern: impl AsRef<str>) -> Parser<Captures, U> {
+        let pattern = pattern.as_ref().to_owned();
+        let regex = Regex::new(&format!(r"\A(?:{pattern})"))
+            .unwrap_or_else(|error| panic!("invalid regex {pattern:?}: {error}"));
+        Parser::i(move |state| {
+            let source = state.text.as_str();
+            let Some(captures) = regex.captures(&source) else {
+                return state.expected(Expected::Pattern(pattern.clone()))
+            };
+            let whole = captures.get(0).unwrap().range();
+            let named = regex
+                .capture_names()
+                .flatten()
+                .filter_map(|name| {
+                    let group = captures.name(name)?;
+                    Some((name.to_owned(), state.text.slice_bytes(group.range())))
+                })
+                .collect();
+            let (text, rest) = state.text.split_at_byte(whole.end);
+            state.set_text(rest).ok(Captures { text, named })
+        })
+    }
+}
@@ -0,0 +1,151 @@
+//! Character classes and the common whitespace, line and word parsers.
+//!
+//! Line endings follow CommonMark: `\n`, `\r\n` or a lone `\r`.
+use unicode_properties::{GeneralCategoryGroup, UnicodeGeneralCategory};
+use unicode_segmentation::UnicodeSegmentation;
+use crate::error::Expected;
+use crate::parser::{CharParser, Parser, TextParser};
+use crate::shared::Shareable;
+use crate::text::Text;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// CHARACTER CLASSES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A character line endings are made of, `\n` or `\r`.
+pub fn is_newline(x: char) -> bool {
+    x == '\n' || x == '\r'
+}
+
+/// Whitespace within a line.
+pub fn is_space(x: char) -> bool {
+    x.is_whitespace() && !is_newline(x)
+}
+
+/// A CommonMark Unicode punctuation character: anything in the Unicode `P`
+/// (punctuation) or `S` (symbol) general categories, which covers all ASCII
+/// punctuation.
+pub fn is_punctuation(x: char) -> bool {
+    matches!(
+        x.general_category_group(),
+        GeneralCategoryGroup::Punctuation | GeneralCategoryGroup::Symbol
+    )
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// CHAR PARSERS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<U> CharParser<U> where U: Clone + Shareable + 'static {
+    /// A `\n` or `\r`; see [`TextParser::line_ending`] to take `\r\n` as one.
+    pub fn newline() -> Self {
+        Self::class("newline", is_newline)
+    }
+    /// Whitespace other than a newline.
+    pub fn space() -> Self {
+        Self::class("space", is_space)
+    }
+    pub fn any_whitespace() -> Self {
+        Self::class("whitespace", char::is_whitespace)
+    }
+    /// Any Unicode numeric character, see [`CharParser::digit`] for ASCII digits.
+    pub fn number() -> Self {
+        Self::class("number", char::is_numeric)
+    }
+    /// See [`is_punctuation`].
+    pub fn punctuation() -> Self {
+        Self::class("punctuation", is_punctuation)
+    }
+    /// Any Unicode alphabetic character.
+    pub fn letter() -> Self {
+        Self::class("letter", char::is_alphabetic)
+    }
+    /// `0` to `9`.
+    pub fn digit() -> Self {
+        Self::class("digit", |x| x.is_ascii_digit())
+    }
+    /// `0` to `9`, `a` to `f` and `A` to `F`.
+    pub fn hex_digit() -> Self {
+        Self::class("hex digit", |x| x.is_ascii_hexdigit())
+    }
+    fn class(name: &'static str, predicate: fn(char) -> bool) -> Self {
+        Self::i(move |state| {
+            match state.text.uncons() {
+                Some((l, r)) if predicate(l.value) => state.set_text(r).ok(l),
+                _ => state.expected(Expected::Label(name.to_owned())),
+            }
+        })
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// TEXT PARSERS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<U> TextParser<U> where U: Clone + Shareable + 'static {
+    /// Zero or more spaces, never a newline.
+    pub fn spaces() -> Self {
+        Self::take_while("spaces", 0, is_space)
+    }
+    /// Zero or more whitespace characters, newlines included.
+    pub fn any_whitespace() -> Self {
+        Self::take_while("whitespace", 0, char::is_whitespace)
+    }
+    /// Everything up to, but not including, the next line ending or the end
+    /// of input. Fails on an empty line.
+    pub fn rest_of_line() -> Self {
+        Self::take_while("rest of line", 1, |x| !is_newline(x))
+    }
+    /// `\r\n`, `\n` or `\r`.
+    pub fn line_ending() -> Self {
+        Self::i(|state| {
+            let ending = ["\r\n", "\n", "\r"]
+                .into_iter()
+                .find_map(|x| state.text.pop_prefix(x));
+            match ending {
+                Some((ending, rest)) => state.set_text(rest).ok(ending),
+                None => state.expected(Expected::Label("line ending".to_owned())),
+            }
+        })
+    }
+    /// A single word, by the Unicode word boundary rules.
+    ///
+    /// Runs of whitespace or punctuation between words are not words.
+    pub fn word() -> Self {
+        Self::i(|state| {
+            let length = state.text.with_prefix(|source, complete| {
+                let word = source.split_word_bounds().next().unwrap_or_default();
+                // The word rules look up to two characters past a boundary.
+                if !complete && source[word.len()..].chars().nth(1).is_none() {
+                    return None
+                }
+                Some(Some(word).filter(|x| x.chars().any(char::is_alphanumeric)).map(str::len))
+            });
+            match length {
+                Some(length) => {
+                    let (word, rest) = state.text.split_at_byte(length);
+                    state.set_text(rest).ok(word)
+                }
+                None => state.expected(Expected::Label("word".to_owned())),
+            }
+        })
+    }
+    /// One or more words separated by [`TextParser::spaces`]. Spaces after the
+    /// last word are left in the input.
+    pub fn words() -> Parser<Vec<Text>, U> {
+        Self::word()
+            .and(Self::spaces().keep(Self::word()).many())
+            .map(|(first, rest)| std::iter::once(first).chain(rest).collect())
+    }
+    /// At least `min` characters matching `predicate`, as one slice of the input.
+    fn take_while(name: &'static str, min: usize, predicate: fn(char) -> bool) -> Self {
+        Self::i(move |state| {
+            let (count, length) = state.text
+                .chars()
+                .take_while(|x| predicate(*x))
+                .fold((0, 0), |(count, length), x| (count + 1, length + x.len_utf8()));
+            if count < min {
+                return state.expected(Expected::Label(name.to_owned()))
+            }
+            let (taken, rest) = state.text.split_at_byte(length);
+            state.set_text(rest).ok(taken)
+        })
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::BTreeSet;
+use colored::{ColoredString, Colorize};
+use unicode_segmentation::UnicodeSegmentation;
+use crate::error::{Diagnostic, ParseError};
+use crate::text::PositionIndex;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// REPORT
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A message about a span of the source, such as a parse error or a lint finding.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub severity: Severity,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+/// An annotated source range; `end` is exclusive.
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub start: PositionIndex,
+    pub end: PositionIndex,
+    pub message: String,
+    pub primary: bool,
+}
+
+impl Report {
+    pub fn new(severity: Severity, message: impl ToString) -> Self {
+        Self {
+            severity,
+            message: message.to_string(),
+            labels: Vec::default(),
+            notes: Vec::default(),
+        }
+    }
+    pub fn error(message: impl ToString) -> Self {
+        Self::new(Severity::Error, message)
+    }
+    pub fn warning(message: impl ToString) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        self
+    }
+    pub fn with_note(mut self, note: impl ToString) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+}
+
+impl Label {
+    pub fn primary(start: PositionIndex, end: PositionIndex, message: impl ToString) -> Self {
+        Self { start, end, message: message.to_string(), primary: true }
+    }
+    pub fn secondary(start: PositionIndex, end: PositionIndex, message: impl ToString) -> Self {
+        Self { start, end, message: message.to_string(), primary: false }
+    }
+}
+
+impl From<&ParseError> for Report {
+    fn from(error: &ParseError) -> Self {
+        let mut end = error.position;
+        if error.found.is_some() {
+            end.column_offset += 1;
+        }
+        let label = Label::primary(error.position, end, error.expected_message().unwrap_or_default());
+        let report = Report::error(error.unexpected_message()).with_label(label);
+        match error.context_message() {
+            Some(context) => report.with_note(context),
+            None => report,
+        }
+    }
+}
+
+impl From<&Diagnostic> for Report {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        let mut report = Report::from(&diagnostic.error);
+        if diagnostic.start != diagnostic.end {
+            report.labels.push(Label::secondary(diagnostic.start, diagnostic.end, "skipped"));
+        }
+        report
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// RENDERER
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Renders [`Report`]s against their source, in the style of rustc's error output.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    use_color: bool,
+    file_name: Option<String>,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self {
+            use_color: true,
+            file_name: None,
+        }
+    }
+}
+
+impl Renderer {
+    pub fn use_color(mut self, flag: bool) -> Self {
+        self.use_color = flag;
+        self
+    }
+    pub fn file_name(mut self, name: impl ToString) -> Self {
+        self.file_name = Some(name.to_string());
+        self
+    }
+    pub fn render(&self, source: &str, report: &Report) -> String {
+        let lines = source
+            .split('\n')
+            .map(|x| x.strip_suffix('\r').unwrap_or(x))
+            .collect::<Vec<_>>();
+        let touched = report.labels
+            .iter()
+            .flat_map(|label| {
+                (label.start.line_offset..=label.end.line_offset)
+                    .filter(|line| label_columns(label, *line, &lines).is_some())
+            })
+            .filter(|line| *line < lines.len())
+            .collect::<BTreeSet<_>>();
+        let width = touched
+            .last()
+            .map(|x| (x + 1).to_string().len())
+            .unwrap_or(1);
+        let padding = " ".repeat(width);
+        let bar = self.paint("|", |x| x.blue().bold());
+        let mut output = Vec::<String>::new();
+        // - HEADER -
+        let severity = match report.severity {
+            Severity::Error => self.paint("error", |x| x.red().bold()),
+            Severity::Warning => self.paint("warning", |x| x.yellow().bold()),
+            Severity::Note => self.paint("note", |x| x.cyan().bold()),
+        };
+        output.push(format!("{severity}: {}", self.paint(&report.message, |x| x.bold())));
+        let anchor = report.labels
+            .iter()
+            .find(|x| x.primary)
+            .or(report.labels.first());
+        if let Some(anchor) = anchor {
+            let arrow = self.paint("-->", |x| x.blue().bold());
+            let file_name = self.file_name
+                .as_ref()
+                .map(|x| format!("{x}:"))
+                .unwrap_or_default();
+            let line = anchor.start.line_offset + 1;
+            let column = anchor.start.column_offset + 1;
+            output.push(format!("{padding}{arrow} {file_name}{line}:{column}"));
+        }
+        // - SNIPPET -
+        if !touched.is_empty() {
+            output.push(format!("{padding} {bar}"));
+        }
+        let mut previous: Option<usize> = None;
+        for line in touched.iter().copied() {
+            if previous.is_some_and(|x| x + 1 < line) {
+                output.push(self.paint("...", |x| x.blue().bold()));
+            }
+            previous = Some(line);
+            let number = self.paint(format!("{:>width$}", line + 1), |x| x.blue().bold());
+            let text = lines[line];
+            output.push(format!("{number} {bar} {text}").trim_end().to_owned());
+            for label in report.labels.iter() {
+                let Some((start, end)) = label_columns(label, line, &lines) else {
+                    continue
+                };
+                let indent = text
+                    .graphemes(true)
+                    .take(start)
+                    .map(|x| if x == "\t" { "\t" } else { " " })
+                    .collect::<String>();
+                let marker = if label.primary { "^" } else { "-" };
+                let mut markers = marker.repeat((end - start).max(1));
+                let is_last_line = label_last_line(label, &lines) == line;
+                if is_last_line && !label.message.is_empty() {
+                    markers.push(' ');
+                    markers.push_str(&label.message);
+                }
+                let markers = match (label.primary, report.severity) {
+                    (false, _) => self.paint(markers, |x| x.blue().bold()),
+                    (true, Severity::Error) => self.paint(markers, |x| x.red().bold()),
+                    (true, Severity::Warning) => self.paint(markers, |x| x.yellow().bold()),
+                    (true, Severity::Note) => self.paint(markers, |x| x.cyan().bold()),
+                };
+                output.push(format!("{padding} {bar} {indent}{markers}"));
+            }
+        }
+        // - NOTES -
+        if !report.notes.is_empty() {
+            output.push(format!("{padding} {bar}"));
+        }
+        for note in report.notes.iter() {
+            let equals = self.paint("=", |x| x.blue().bold());
+            output.push(format!("{padding} {equals} {}: {note}", self.paint("note", |x| x.bold())));
+        }
+        output.join("\n")
+    }
+    fn paint(&self, text: impl AsRef<str>, style: impl FnOnce(ColoredString) -> ColoredString) -> String {
+        if self.use_color {
+            return style(text.as_ref().into()).to_string()
+        }
+        text.as_ref().to_owned()
+    }
+}
+
+/// The grapheme columns `label` covers on `line`, if any.
+fn label_columns(label: &Label, line: usize, lines: &[&str]) -> Option<(usize, usize)> {
+    let text = lines.get(line)?;
+    let length = text.graphemes(true).count();
+    let start = if line == label.start.line_offset { label.start.column_offset } else { 0 };
+    let end = if line == label.end.line_offset { label.end.column_offset } else { length };
+    let is_empty_label = label.start == label.end;
+    if end > start || (is_empty_label && line == label.start.line_offset) {
+        return Some((start, end.max(start)))
+    }
+    // A span ending right after a newline still covers the line it started on.
+    if line == label.start.line_offset && start >= length {
+        return Some((start, start))
+    }
+    None
+}
+
+fn label_last_line(label: &Label, lines: &[&str]) -> usize {
+    (label.start.line_offset..=label.end.line_offset)
+        .rev()
+        .find(|line| label_columns(label, *line, lines).is_some())
+        .unwrap_or(label.start.line_offset)
+}
@@ -0,0 +1,37 @@
+//! Pointer and cell types used inside parsers and `Text`.
+//!
+//! By default these are the single-threaded `Rc` and `OnceCell`. With the
+//! `sync` feature they become `Arc` and `OnceLock`, and every closure and
+//! value captured by a parser must be `Send + Sync`, so a grammar can be
+//! built once and shared between threads.
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SHARED
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[cfg(not(feature = "sync"))]
+pub(crate) type Shared<T> = std::rc::Rc<T>;
+#[cfg(not(feature = "sync"))]
+pub(crate) type SharedWeak<T> = std::rc::Weak<T>;
+#[cfg(not(feature = "sync"))]
+pub(crate) type SharedCell<T> = std::cell::OnceCell<T>;
+
+#[cfg(feature = "sync")]
+pub(crate) type Shared<T> = std::sync::Arc<T>;
+#[cfg(feature = "sync")]
+pub(crate) type SharedWeak<T> = std::sync::Weak<T>;
+#[cfg(feature = "sync")]
+pub(crate) type SharedCell<T> = std::sync::OnceLock<T>;
+
+/// Bound on everything a parser captures: `Send + Sync` with the `sync`
+/// feature, and no requirement at all without it.
+#[cfg(not(feature = "sync"))]
+pub trait Shareable {}
+#[cfg(not(feature = "sync"))]
+impl<T: ?Sized> Shareable for T {}
+
+/// Bound on everything a parser captures: `Send + Sync` with the `sync`
+/// feature, and no requirement at all without it.
+#[cfg(feature = "sync")]
+pub trait Shareable: Send + Sync {}
+#[cfg(feature = "sync")]
+impl<T: ?Sized + Send + Sync> Shareable for T {}
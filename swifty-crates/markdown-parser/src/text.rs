@@ -1,48 +1,82 @@
+use std::borrow::Cow;
+use std::ops::Range;
 use itertools::Itertools;
-use unicode_segmentation::UnicodeSegmentation;
-use std::rc::Rc;
 use tree_formatter::{PrettyTree, ToPrettyTree};
+use crate::line_index::LineIndex;
+use crate::shared::{Shared, SharedCell};
 
+/// A persistent list of characters, e.g. to collect into a [`Text`] with
+/// [`Text::from_iter`].
 pub type FatCharList = im_lists::list::List<FatChar>;
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-// SECTION NAME
+// TEXT
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A view into one or more shared source strings.
+///
+/// Slicing never copies characters: a `Text` is a list of byte ranges plus a
+/// cursor into it, so `take`/`pop_prefix` only walk the characters they split
+/// off. Positions are looked up on demand, each lookup being a binary search
+/// of the source's [`LineIndex`], so `uncons` is O(log n) rather than O(1).
 #[derive(Clone)]
 pub struct Text {
-    data: FatCharList
+    /// In reading order, never empty; only the last span may be empty, marking
+    /// where the text ends.
+    spans: Shared<[Span]>,
+    /// The span holding the first character.
+    first: usize,
+    /// The byte offset of the first character within `spans[first]`.
+    offset: usize,
+}
+
+#[derive(Clone)]
+struct Span {
+    source: Shared<Source>,
+    range: Range<usize>,
 }
 
 impl Text {
     pub fn initialize_from(source: impl AsRef<str>) -> Self {
-        let source = source.as_ref().to_owned();
-        Self {
-            data: FatCharList::from_iter(to_fat_chars(&source)),
-        }
+        let source = Source::new(source.as_ref().to_owned());
+        let range = 0..source.text.len();
+        Self::from_spans(vec![Span { source: Shared::new(source), range }])
     }
+    /// Collects characters into a new text; each keeps its original position.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_iter(list: impl IntoIterator<Item=FatChar>) -> Self {
-        Self {
-            data: list.into_iter().collect()
-        }
+        let chars = list.into_iter().collect_vec();
+        let source = Source::from_chars(chars);
+        let range = 0..source.text.len();
+        Self::from_spans(vec![Span { source: Shared::new(source), range }])
     }
-    pub fn start(&self) -> Option<&FatChar> {
-        self.data.first()
+    /// The first character, by value: positions are resolved on demand, so
+    /// there is no stored [`FatChar`] to borrow.
+    pub fn start(&self) -> Option<FatChar> {
+        let span = &self.spans[self.first];
+        let value = span.source.text[self.offset..span.range.end].chars().next()?;
+        Some(span.source.fat_char(self.offset, value))
     }
-    pub fn end(&self) -> Option<&FatChar> {
-        self.data.last()
+    /// The last character, by value like [`Text::start`].
+    pub fn end(&self) -> Option<FatChar> {
+        let (source, range) = self.live_spans().filter(|(_, x)| !x.is_empty()).last()?;
+        let value = source.text[range.clone()].chars().next_back()?;
+        Some(source.fat_char(range.end - value.len_utf8(), value))
     }
     pub fn start_index(&self) -> Option<PositionIndex> {
-        self.data.first().map(|x| x.index)
+        self.start().map(|x| x.index)
     }
     pub fn end_index(&self) -> Option<PositionIndex> {
-        self.data.last().map(|x| x.index)
+        self.end().map(|x| x.index)
+    }
+    /// The position of the first character, or the end-of-input position when empty.
+    pub fn position(&self) -> PositionIndex {
+        self.spans[self.first].source.position_at(self.offset)
     }
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.offset == self.spans[self.first].range.end
     }
     pub fn has_prefix(&self, prefix: impl AsRef<str>) -> bool {
         let prefix = prefix.as_ref();
-        let prefix_chars = prefix.chars().collect_vec();
         // - -
         if self.is_empty() && prefix.is_empty() {
             return true
@@ -50,56 +84,219 @@ impl Text {
         if self.is_empty() || prefix.is_empty() {
             return false
         }
-        if self.data.len() < prefix_chars.len() {
-            return false
+        let mut rest = prefix;
+        for part in self.strs() {
+            if rest.len() <= part.len() {
+                return part.starts_with(rest)
+            }
+            if !rest.starts_with(part) {
+                return false
+            }
+            rest = &rest[part.len()..];
         }
-        let is_match = self.data
-            .iter()
-            .zip(prefix_chars)
-            .all(|(l, r)| {
-                l.value == r
-            });
-        // - -
-        return is_match
+        false
     }
     pub fn pop_prefix(&self, prefix: impl AsRef<str>) -> Option<(Self, Self)> {
         let prefix = prefix.as_ref();
-        let prefix_len = prefix.chars().count();
         if !self.has_prefix(prefix) {
             return None
         }
-        let leading = self.data.take(prefix_len);
-        let trailing = self.data.tail(prefix_len)?;
-        let is_valid = leading
-            .iter()
-            .zip(prefix.chars())
-            .all(|(l, r)| {
-                l.value == r
-            });
-        assert!(is_valid);
-        let leading = self.set_data(leading);
-        let trailing = self.set_data(trailing);
-        Some((leading, trailing))
+        Some(self.split_at_byte(prefix.len()))
     }
     pub fn take(&self, count: usize) -> Option<(Self, Self)> {
-        if self.data.len() < count {
+        let mut remaining = count;
+        let mut bytes = 0;
+        for part in self.strs() {
+            for (index, _) in part.char_indices() {
+                if remaining == 0 {
+                    return Some(self.split_at_byte(bytes + index))
+                }
+                remaining -= 1;
+            }
+            bytes += part.len();
+        }
+        if remaining > 0 {
             return None
         }
-        let leading = self.set_data(
-            self.data.take(count)
-        );
-        let trailing = self.set_data(
-            self.data.tail(count)?
-        );
-        Some((leading, trailing))
+        Some(self.split_at_byte(bytes))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = FatChar> + '_ {
+        self.live_spans().flat_map(|(source, range)| source.chars(range))
+    }
+    pub fn concat(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return other.clone()
+        }
+        let spans = self.live_spans()
+            .chain(other.live_spans())
+            .map(|(source, range)| Span { source: source.clone(), range })
+            .collect_vec();
+        Self::from_spans(spans)
+    }
+    /// The suffix of this text starting at `position`, if that position lies within it.
+    pub fn seek(&self, position: PositionIndex) -> Option<Self> {
+        let count = self
+            .iter()
+            .position(|x| x.index.scalar_offset == position.scalar_offset)?;
+        self.take(count).map(|(_, rest)| rest)
+    }
+    /// Splits off any trailing whitespace, returning `(content, whitespace)`.
+    ///
+    /// Newlines only count as whitespace when `include_newlines` is set.
+    pub fn trim_trailing(&self, include_newlines: bool) -> (Self, Self) {
+        let parts = self.strs().collect_vec();
+        let total = parts.iter().map(|x| x.len()).sum::<usize>();
+        let trailing = parts
+            .iter()
+            .rev()
+            .flat_map(|x| x.chars().rev())
+            .take_while(|x| x.is_whitespace() && (include_newlines || *x != '\n'))
+            .map(char::len_utf8)
+            .sum::<usize>();
+        self.split_at_byte(total - trailing)
     }
     pub fn uncons(&self) -> Option<(FatChar, Self)> {
-        let mut copy = self.data.clone();
-        let first = copy.pop_front()?;
-        Some((first, self.set_data(copy)))
+        let first = self.start()?;
+        let rest = self.advance(self.first, self.offset + first.value.len_utf8());
+        Some((first, rest))
     }
-    fn set_data(&self, data: FatCharList) -> Self {
-        Self { data }
+    /// Drops empty spans, except a trailing one that keeps the end position.
+    ///
+    /// There is always at least that one span, over an empty source if need be.
+    fn from_spans(mut spans: Vec<Span>) -> Self {
+        let last = spans.pop().unwrap_or_else(|| {
+            Span { source: Shared::new(Source::from_chars(Vec::new())), range: 0..0 }
+        });
+        spans.retain(|x| !x.range.is_empty());
+        spans.push(last);
+        let offset = spans[0].range.start;
+        Self { spans: Shared::from(spans), first: 0, offset }
+    }
+    /// Moves the cursor, skipping over exhausted spans.
+    fn advance(&self, mut first: usize, mut offset: usize) -> Self {
+        while offset == self.spans[first].range.end && first + 1 < self.spans.len() {
+            first += 1;
+            offset = self.spans[first].range.start;
+        }
+        Self { spans: self.spans.clone(), first, offset }
+    }
+    /// The characters of this text as one string, borrowed when it is a single span.
+    pub(crate) fn as_str(&self) -> Cow<'_, str> {
+        let mut parts = self.strs();
+        match (parts.next(), parts.next()) {
+            (None, _) => Cow::Borrowed(""),
+            (Some(part), None) => Cow::Borrowed(part),
+            (Some(_), Some(_)) => Cow::Owned(self.strs().collect()),
+        }
+    }
+    /// Runs `f` over a prefix of this text, doubling the prefix for as long as
+    /// `f` asks for more input by returning `None`.
+    ///
+    /// `f` is also told whether the prefix is the whole text, and must return
+    /// `Some` when it is. Prefixes that fit in one span are borrowed.
+    pub(crate) fn with_prefix<R>(&self, f: impl Fn(&str, bool) -> Option<R>) -> R {
+        let total = self.byte_len();
+        let mut bytes = 64;
+        loop {
+            let prefix = self.prefix_str(bytes);
+            let complete = prefix.len() == total;
+            match f(&prefix, complete) {
+                Some(result) => return result,
+                None if complete => panic!("with_prefix: no result for the whole text"),
+                None => bytes *= 2,
+            }
+        }
+    }
+    /// At most the first `bytes` bytes of this text, cut back to a character boundary.
+    fn prefix_str(&self, bytes: usize) -> Cow<'_, str> {
+        let mut parts = self.strs();
+        let Some(first) = parts.next() else {
+            return Cow::Borrowed("")
+        };
+        if first.len() >= bytes || self.first + 1 == self.spans.len() {
+            return Cow::Borrowed(&first[..floor_char_boundary(first, bytes)])
+        }
+        let mut prefix = first.to_owned();
+        for part in parts {
+            if prefix.len() >= bytes {
+                break
+            }
+            prefix.push_str(part);
+        }
+        prefix.truncate(floor_char_boundary(&prefix, bytes));
+        Cow::Owned(prefix)
+    }
+    /// The part of this text consumed to reach `rest`, one of its suffixes.
+    pub(crate) fn consumed(&self, rest: &Self) -> Self {
+        let bytes = self.byte_len().saturating_sub(rest.byte_len());
+        self.split_at_byte(bytes).0
+    }
+    /// Whether both texts are at the same point of the same input.
+    ///
+    /// Positions can repeat once text is pushed back or concatenated, so this
+    /// compares the underlying source and byte offset instead.
+    pub(crate) fn same_cursor(&self, other: &Self) -> bool {
+        let (left, right) = (&self.spans[self.first], &other.spans[other.first]);
+        Shared::ptr_eq(&left.source, &right.source)
+            && self.offset == other.offset
+            && self.byte_len() == other.byte_len()
+    }
+    /// The number of bytes left in this text.
+    pub(crate) fn byte_len(&self) -> usize {
+        self.live_spans().map(|(_, range)| range.len()).sum()
+    }
+    /// The plain characters of this text, skipping position lookups.
+    pub(crate) fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.strs().flat_map(str::chars)
+    }
+    /// The part of this text between two byte offsets into [`Text::as_str`].
+    pub(crate) fn slice_bytes(&self, range: Range<usize>) -> Self {
+        let (leading, _) = self.split_at_byte(range.end);
+        leading.split_at_byte(range.start).1
+    }
+    /// Splits after `bytes` bytes of this text, which must fall on a character boundary.
+    pub(crate) fn split_at_byte(&self, bytes: usize) -> (Self, Self) {
+        let mut leading = Vec::<Span>::new();
+        let mut remaining = bytes;
+        for (index, (source, range)) in self.live_spans().enumerate() {
+            if remaining <= range.len() {
+                let cut = range.start + remaining;
+                leading.push(Span { source: source.clone(), range: range.start..cut });
+                let trailing = self.advance(self.first + index, cut);
+                return (Self::from_spans(leading), trailing)
+            }
+            remaining -= range.len();
+            leading.push(Span { source: source.clone(), range });
+        }
+        (self.clone(), self.advance(self.first, self.offset))
+    }
+    fn live_spans(&self) -> impl Iterator<Item = (&Shared<Source>, Range<usize>)> + '_ {
+        self.spans
+            .iter()
+            .enumerate()
+            .skip(self.first)
+            .map(|(index, span)| {
+                let start = if index == self.first { self.offset } else { span.range.start };
+                (&span.source, start..span.range.end)
+            })
+    }
+    fn strs(&self) -> impl Iterator<Item = &str> + '_ {
+        self.live_spans().map(|(source, range)| &source.text[range])
+    }
+}
+
+/// The largest character boundary of `value` at or before `bytes`.
+fn floor_char_boundary(value: &str, bytes: usize) -> usize {
+    let mut bytes = bytes.min(value.len());
+    while !value.is_char_boundary(bytes) {
+        bytes -= 1;
+    }
+    bytes
+}
+
+impl FromIterator<FatChar> for Text {
+    fn from_iter<I: IntoIterator<Item = FatChar>>(iter: I) -> Self {
+        Text::from_iter(iter)
     }
 }
 
@@ -112,11 +309,91 @@ impl std::fmt::Debug for Text {
 }
 impl std::fmt::Display for Text {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let chars = self.data
-            .iter()
-            .map(|x| x.value)
-            .collect::<String>();
-        write!(f, "{chars}")
+        for part in self.strs() {
+            write!(f, "{part}")?;
+        }
+        Ok(())
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SOURCE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A string shared by every `Text` sliced from it.
+struct Source {
+    text: String,
+    /// Built on first use, or given up front by [`Source::from_chars`].
+    positions: SharedCell<Positions>,
+}
+
+enum Positions {
+    /// Positions within `text` itself.
+    Indexed(LineIndex),
+    /// Positions carried over from another source.
+    ///
+    /// Only scalars whose position does not follow arithmetically from the
+    /// previous one are anchored: the first, and those after a newline, a
+    /// non-ASCII scalar or a gap in the original. Contiguous ASCII runs cost
+    /// nothing.
+    Explicit {
+        /// The byte offset within [`Source::text`] of each anchored scalar,
+        /// and its position, sorted by byte offset.
+        anchors: Vec<(usize, PositionIndex)>,
+        end: PositionIndex,
+    },
+}
+
+impl Source {
+    fn new(text: String) -> Self {
+        Self { text, positions: SharedCell::new() }
+    }
+    /// A source made of characters taken from elsewhere, keeping their positions.
+    fn from_chars(chars: Vec<FatChar>) -> Self {
+        let mut text = String::new();
+        let mut anchors = Vec::<(usize, PositionIndex)>::new();
+        let mut previous: Option<&FatChar> = None;
+        for char in chars.iter() {
+            let follows = previous.is_some_and(|x| {
+                x.value.is_ascii() && x.value != '\n' && x.end_index() == char.index
+            });
+            if !follows {
+                anchors.push((text.len(), char.index));
+            }
+            text.push(char.value);
+            previous = Some(char);
+        }
+        let end = previous.map(FatChar::end_index).unwrap_or(PositionIndex::ZERO);
+        let positions = SharedCell::from(Positions::Explicit { anchors, end });
+        Self { text, positions }
+    }
+    fn positions(&self) -> &Positions {
+        self.positions.get_or_init(|| Positions::Indexed(LineIndex::new(&self.text)))
+    }
+    /// The position of the scalar starting at `byte`, or the end position past the last one.
+    fn position_at(&self, byte: usize) -> PositionIndex {
+        match self.positions() {
+            Positions::Indexed(index) => index.position(byte),
+            Positions::Explicit { end, .. } if byte >= self.text.len() => *end,
+            Positions::Explicit { anchors, .. } => {
+                let index = anchors.partition_point(|(x, _)| *x <= byte) - 1;
+                let (anchor, mut position) = anchors[index];
+                let distance = byte - anchor;
+                position.byte_offset += distance;
+                position.scalar_offset += distance;
+                position.grapheme_offset += distance;
+                position.column_offset += distance;
+                position
+            }
+        }
+    }
+    fn fat_char(&self, byte: usize, value: char) -> FatChar {
+        FatChar { index: self.position_at(byte), value }
+    }
+    fn chars(&self, range: Range<usize>) -> impl Iterator<Item = FatChar> + '_ {
+        let start = range.start;
+        self.text[range]
+            .char_indices()
+            .map(move |(offset, value)| self.fat_char(start + offset, value))
     }
 }
 
@@ -129,10 +406,27 @@ pub struct FatChar {
     pub value: char,
 }
 
+impl FatChar {
+    /// The position immediately following this character.
+    pub fn end_index(&self) -> PositionIndex {
+        let mut index = self.index;
+        index.byte_offset += self.value.len_utf8();
+        index.scalar_offset += 1;
+        index.grapheme_offset += 1;
+        if self.value == '\n' {
+            index.line_offset += 1;
+            index.column_offset = 0;
+        } else {
+            index.column_offset += 1;
+        }
+        index
+    }
+}
+
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PositionIndex {
     pub grapheme_offset: usize,
     pub scalar_offset: usize,
@@ -151,40 +445,6 @@ impl PositionIndex {
     };
 }
 
-//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-// SECTION NAME
-//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-fn to_fat_chars(source: impl AsRef<str>) -> Vec<FatChar> {
-    let mut leading_position = PositionIndex::ZERO;
-    source
-        .as_ref()
-        .grapheme_indices(true)
-        .flat_map(|(byte_offset, graphme)| {
-            // - UPDATE LEADING STATE -
-            let mut results: Vec<FatChar> = Vec::new();
-            // - -
-            leading_position.byte_offset = byte_offset;
-            // - -
-            for char in graphme.chars() {
-                results.push(FatChar {
-                    index: leading_position.clone(),
-                    value: char
-                });
-                // - -
-                leading_position.scalar_offset += 1;
-                if char == '\n' {
-                    leading_position.line_offset += 1;
-                    leading_position.column_offset = 0;
-                }
-            }
-            // - -
-            leading_position.grapheme_offset += 1;
-            leading_position.column_offset += 1;
-            results
-        })
-        .collect_vec()
-}
-
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // DEBUG
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
@@ -0,0 +1,185 @@
+//! Token matching that looks past case and Unicode normalization, and
+//! longest-match sets of literal tokens.
+use std::collections::BTreeMap;
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::error::Expected;
+use crate::parser::{Parser, TextParser, TupleParser};
+use crate::shared::Shareable;
+use crate::text::Text;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SETTINGS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// How [`TextParser::token_with`] compares the input with a token.
+///
+/// The default compares exactly, like [`TextParser::token`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenSettings {
+    fold_case: bool,
+    normalize: bool,
+}
+
+impl TokenSettings {
+    /// Compares under Unicode full case folding, which includes the simple
+    /// foldings, so `"DIV"` matches `"div"` and `"ß"` matches `"SS"`.
+    pub fn fold_case(mut self, flag: bool) -> Self {
+        self.fold_case = flag;
+        self
+    }
+    /// Compares canonical decompositions (NFD), so precomposed and combining
+    /// forms of the same character match.
+    pub fn normalize(mut self, flag: bool) -> Self {
+        self.normalize = flag;
+        self
+    }
+    /// The form `value` is compared in.
+    fn canonical(&self, value: &str) -> String {
+        match (self.fold_case, self.normalize) {
+            (false, false) => value.to_owned(),
+            (true, false) => value.chars().default_case_fold().collect(),
+            (false, true) => value.nfd().collect(),
+            (true, true) => value.nfd().default_case_fold().nfd().collect(),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSERS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<U> TextParser<U> where U: Clone + Shareable + 'static {
+    /// Matches `value` as compared under `settings`.
+    ///
+    /// The input is consumed one grapheme cluster at a time, so a match never
+    /// ends inside one. The result is the original input, with its positions.
+    pub fn token_with(value: impl ToString, settings: TokenSettings) -> Self {
+        let value = value.to_string();
+        let target = settings.canonical(&value);
+        Self::i(move |state| {
+            let length = state.text.with_prefix(|source, complete| {
+                let mut graphemes = source.grapheme_indices(true);
+                let mut canonical = String::new();
+                let mut consumed = 0;
+                loop {
+                    if canonical == target {
+                        return Some(Some(consumed))
+                    }
+                    let Some((offset, grapheme)) = graphemes.next() else {
+                        return complete.then_some(None)
+                    };
+                    // A cluster reaching the end of a partial prefix may go on past it.
+                    if !complete && offset + grapheme.len() == source.len() {
+                        return None
+                    }
+                    canonical.push_str(&settings.canonical(grapheme));
+                    consumed = offset + grapheme.len();
+                    if !target.starts_with(&canonical) {
+                        return Some(None)
+                    }
+                }
+            });
+            match length {
+                Some(length) => {
+                    let (token, rest) = state.text.split_at_byte(length);
+                    state.set_text(rest).ok(token)
+                }
+                None => state.expected(Expected::Token(value.clone())),
+            }
+        })
+    }
+    /// Shorthand for [`TextParser::token_with`] with case folding.
+    pub fn token_ignore_case(value: impl ToString) -> Self {
+        Self::token_with(value, TokenSettings::default().fold_case(true))
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// TOKEN SETS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<U> TextParser<U> where U: Clone + Shareable + 'static {
+    /// Matches the longest of `tokens`, returning its index and the matched text.
+    ///
+    /// The tokens are compiled into a trie, so the order they are listed in
+    /// does not matter and each attempt reads the input only once.
+    pub fn one_of(tokens: &[impl AsRef<str>]) -> TupleParser<usize, Text, U> {
+        Parser::one_of_values(tokens.iter().map(AsRef::as_ref).enumerate().map(|(x, y)| (y, x)))
+    }
+    /// Like [`TextParser::one_of`], returning the value paired with the token.
+    ///
+    /// If a token is listed more than once, its first value is used.
+    pub fn one_of_values<T>(entries: impl IntoIterator<Item = (impl ToString, T)>) -> TupleParser<T, Text, U>
+    where
+        T: Clone + Shareable + 'static,
+    {
+        let mut trie = Trie::default();
+        let mut tokens = Vec::new();
+        for (token, value) in entries {
+            let token = token.to_string();
+            trie.insert(&token, value);
+            tokens.push(token);
+        }
+        Parser::i(move |state| {
+            match trie.longest_match(&state.text) {
+                Some((value, length)) => {
+                    let (token, rest) = state.text.split_at_byte(length);
+                    state.set_text(rest).ok((value.clone(), token))
+                }
+                None => {
+                    state.report(tokens.iter().cloned().map(Expected::Token));
+                    state.fail()
+                }
+            }
+        })
+    }
+}
+
+struct Trie<T> {
+    nodes: Vec<Node<T>>,
+}
+
+struct Node<T> {
+    children: BTreeMap<char, usize>,
+    value: Option<T>,
+}
+
+impl<T> Default for Trie<T> {
+    fn default() -> Self {
+        Self { nodes: vec![Node { children: BTreeMap::new(), value: None }] }
+    }
+}
+
+impl<T> Trie<T> {
+    fn insert(&mut self, token: &str, value: T) {
+        let mut node = 0;
+        for char in token.chars() {
+            node = match self.nodes[node].children.get(&char) {
+                Some(child) => *child,
+                None => {
+                    self.nodes.push(Node { children: BTreeMap::new(), value: None });
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(char, child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].value.get_or_insert(value);
+    }
+    /// The value of the longest token `text` starts with, and that token's length in bytes.
+    fn longest_match(&self, text: &Text) -> Option<(&T, usize)> {
+        let mut node = 0;
+        let mut length = 0;
+        let mut longest = self.nodes[0].value.as_ref().map(|x| (x, 0));
+        for char in text.chars() {
+            let Some(child) = self.nodes[node].children.get(&char) else {
+                break
+            };
+            node = *child;
+            length += char.len_utf8();
+            if let Some(value) = self.nodes[node].value.as_ref() {
+                longest = Some((value, length));
+            }
+        }
+        longest
+    }
+}
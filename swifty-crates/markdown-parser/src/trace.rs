@@ -0,0 +1,113 @@
+use tree_formatter::{PrettyTree, ToPrettyTree};
+use crate::text::{PositionIndex, Text};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// TRACE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The tree of traced parser attempts recorded by [`crate::parser::Parser::evaluate_traced`].
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    pub entries: Vec<TraceEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    pub label: String,
+    pub start: PositionIndex,
+    pub end: PositionIndex,
+    pub outcome: Outcome,
+    /// The consumed text, truncated to [`TraceEntry::PREVIEW_LENGTH`] characters.
+    pub preview: String,
+    pub children: Vec<TraceEntry>,
+}
+
+impl TraceEntry {
+    pub const PREVIEW_LENGTH: usize = 24;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    Fail,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// RECORDER
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Builds a [`Trace`] from nested enter/exit calls.
+#[derive(Debug, Default)]
+pub(crate) struct TraceRecorder {
+    /// Entries that have been entered but not yet exited, innermost last.
+    open: Vec<TraceEntry>,
+    finished: Vec<TraceEntry>,
+}
+
+impl TraceRecorder {
+    pub(crate) fn enter(&mut self, label: &str, start: PositionIndex) {
+        self.open.push(TraceEntry {
+            label: label.to_owned(),
+            start,
+            end: start,
+            outcome: Outcome::Fail,
+            preview: String::default(),
+            children: Vec::default(),
+        });
+    }
+    pub(crate) fn exit(&mut self, outcome: Outcome, input: &Text, rest: &Text) {
+        let Some(mut entry) = self.open.pop() else {
+            return
+        };
+        entry.end = rest.position();
+        entry.outcome = outcome;
+        if outcome == Outcome::Ok {
+            entry.preview = preview(&input.consumed(rest));
+        }
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(entry),
+            None => self.finished.push(entry),
+        }
+    }
+    pub(crate) fn finish(self) -> Trace {
+        Trace { entries: self.finished }
+    }
+}
+
+fn preview(consumed: &Text) -> String {
+    let mut chars = consumed.chars();
+    let mut preview = chars
+        .by_ref()
+        .take(TraceEntry::PREVIEW_LENGTH)
+        .collect::<String>();
+    if chars.next().is_some() {
+        preview.push('…');
+    }
+    preview
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DEBUG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn format_position(position: &PositionIndex) -> String {
+    format!("{}:{}", position.line_offset + 1, position.column_offset + 1)
+}
+
+impl ToPrettyTree for Trace {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::branch_of("Trace", &self.entries)
+    }
+}
+impl ToPrettyTree for TraceEntry {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        let outcome = match self.outcome {
+            Outcome::Ok => "ok",
+            Outcome::Fail => "fail",
+        };
+        let start = format_position(&self.start);
+        let end = format_position(&self.end);
+        let mut label = format!("{} [{start}..{end}] {outcome}", self.label);
+        if !self.preview.is_empty() {
+            label.push_str(&format!(" {:?}", self.preview));
+        }
+        PrettyTree::branch_of(label, &self.children)
+    }
+}
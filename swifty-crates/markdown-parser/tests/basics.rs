@@ -0,0 +1,90 @@
+mod common;
+
+use either::Either;
+use markdown_parser::parser::*;
+use markdown_parser::text::Text;
+use common::run;
+
+#[test]
+fn options_returns_first_success() {
+    let parser = TextParser::options([
+        TextParser::token("ab"),
+        TextParser::token("abc"),
+        TextParser::token("x"),
+    ]);
+    let (output, rest) = run("abcd", parser.clone());
+    assert_eq!(output.unwrap().to_string(), "ab");
+    assert_eq!(rest, "cd");
+    let (output, rest) = run("xyz", parser.clone());
+    assert_eq!(output.unwrap().to_string(), "x");
+    assert_eq!(rest, "yz");
+    let (output, rest) = run("zzz", parser);
+    assert!(output.is_none());
+    assert_eq!(rest, "zzz");
+}
+
+#[test]
+fn or_backtracks_to_the_original_input() {
+    let left = TextParser::token("a").keep(TextParser::token("b"));
+    let right = TextParser::token("ac");
+    let (output, rest) = run("acd", left.or(right));
+    assert_eq!(output.unwrap().to_string(), "ac");
+    assert_eq!(rest, "d");
+}
+
+#[test]
+fn either_tags_the_successful_side() {
+    let parser = || CharParser::char('a').either(TextParser::token("bc"));
+    let (output, _) = run("a", parser());
+    assert!(matches!(output, Some(Either::Left(x)) if x.value == 'a'));
+    let (output, rest) = run("bcd", parser());
+    assert!(matches!(output, Some(Either::Right(x)) if x.to_string() == "bc"));
+    assert_eq!(rest, "d");
+    let (output, rest) = run("d", parser());
+    assert!(output.is_none());
+    assert_eq!(rest, "d");
+}
+
+#[test]
+fn keep_and_ignore_select_one_side() {
+    let keep = CharParser::char('<').keep(TextParser::token("tag"));
+    let (output, _) = run("<tag", keep);
+    assert_eq!(output.unwrap().to_string(), "tag");
+    let ignore = TextParser::token("tag").ignore(CharParser::char('>'));
+    let (output, rest) = run("tag>!", ignore);
+    assert_eq!(output.unwrap().to_string(), "tag");
+    assert_eq!(rest, "!");
+    let (output, _) = run("tag!", TextParser::token("tag").ignore(CharParser::char('>')));
+    assert!(output.is_none());
+}
+
+#[test]
+fn optional_never_fails() {
+    let (output, rest) = run("b", CharParser::char('a').optional());
+    assert!(matches!(output, Some(None)));
+    assert_eq!(rest, "b");
+    let (output, rest) = run("ab", CharParser::char('a').optional());
+    assert!(matches!(output, Some(Some(x)) if x.value == 'a'));
+    assert_eq!(rest, "b");
+}
+
+#[test]
+fn between_returns_the_delimiters_and_content() {
+    let content = CharParser::char_if(char::is_alphabetic).some().map(Text::from_iter);
+    let (output, rest) = run("*em* rest", content.clone().between(CharParser::char('*')));
+    let (open, value, close) = output.unwrap();
+    assert_eq!((open.value, value.to_string(), close.value), ('*', "em".to_owned(), '*'));
+    assert_eq!(rest, " rest");
+    let (output, _) = run("[link]", content.between2(CharParser::char('['), CharParser::char(']')));
+    let (open, value, close) = output.unwrap();
+    assert_eq!((open.value, value.to_string(), close.value), ('[', "link".to_owned(), ']'));
+}
+
+#[test]
+fn put_back_prepends_text() {
+    let (prefix, _) = Text::initialize_from("ab").take(2).unwrap();
+    let parser = TextParser::token("abc").put_back(prefix);
+    let (output, rest) = run("cd", parser);
+    assert_eq!(output.unwrap().to_string(), "abc");
+    assert_eq!(rest, "d");
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use markdown_parser::parser::*;
+use markdown_parser::text::Text;
+use common::run;
+
+fn blockquote() -> TextParser {
+    UnitParser::lines(TextParser::token("> "), ControlFlowParser::noop(), true).map(|x| x.content)
+}
+
+#[test]
+fn bounded_parses_the_extracted_region() {
+    let parser = UnitParser::bounded(blockquote(), CharParser::next().many());
+    let (output, rest) = run("> a b\n> c\nrest", parser);
+    let chars = output.unwrap();
+    assert_eq!(Text::from_iter(chars.clone()).to_string(), "a b\nc");
+    let c = chars.iter().find(|x| x.value == 'c').unwrap();
+    assert_eq!((c.index.line_offset, c.index.column_offset), (1, 2));
+    assert_eq!(rest, "\nrest");
+}
+
+#[test]
+fn bounded_resumes_where_the_subparser_stopped() {
+    let parser = UnitParser::bounded(blockquote(), CharParser::char('a'));
+    let (output, rest) = run("> a b\n> c\nrest", parser);
+    assert_eq!(output.unwrap().value, 'a');
+    assert_eq!(rest, " b\n> c\nrest");
+}
+
+#[test]
+fn bounded_fails_when_the_subparser_fails() {
+    let parser = UnitParser::bounded(blockquote(), CharParser::char('x'));
+    let (output, rest) = run("> a\n", parser);
+    assert!(output.is_none());
+    assert_eq!(rest, "> a\n");
+}
+
+#[test]
+fn bounded_fork_syncs_into_the_outer_input() {
+    let parser = TextParser::token("ab")
+        .peek()
+        .and_then(|region| UnitParser::bounded_fork(region, CharParser::char('a')));
+    let (output, rest) = run("abc", parser);
+    assert_eq!(output.unwrap().value, 'a');
+    assert_eq!(rest, "bc");
+}
+
+#[test]
+fn bounded_fork_leaves_the_outer_input_alone() {
+    let parser = UnitParser::bounded_fork(Text::initialize_from("xy"), TextParser::token("xy"));
+    let (output, rest) = run("abc", parser);
+    assert_eq!(output.unwrap().to_string(), "xy");
+    assert_eq!(rest, "abc");
+}
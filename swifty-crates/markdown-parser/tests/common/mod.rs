@@ -0,0 +1,7 @@
+use markdown_parser::parser::Parser;
+
+/// Evaluates `parser` over `source`, returning its output and the input left over.
+pub fn run<T>(source: &str, parser: Parser<T>) -> (Option<T>, String) {
+    let (output, state) = Parser::evaluate(source, parser);
+    (output, state.text.to_string())
+}
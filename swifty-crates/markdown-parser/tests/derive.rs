@@ -0,0 +1,95 @@
+mod common;
+
+use markdown_parser::parse::Parse;
+use markdown_parser::text::{FatChar, Text};
+use common::run;
+
+#[derive(Debug, Clone, Parse)]
+struct Word {
+    #[parse(some, char_if = char::is_alphanumeric)]
+    text: Text,
+}
+
+#[derive(Debug, Clone, Parse)]
+#[parse(skip_whitespace)]
+struct Link {
+    #[parse(before = "[", after = "]")]
+    label: Word,
+    #[parse(before = "(", after = ")")]
+    target: Word,
+    #[parse(skip)]
+    visited: bool,
+}
+
+#[derive(Debug, Clone, Parse)]
+#[parse(skip_whitespace)]
+enum Expr {
+    Call(Word, #[parse(before = "(", after = ")", many)] Vec<Expr>),
+    #[parse(token = "-")]
+    Negate(Box<Expr>),
+    Atom(Word),
+}
+
+#[derive(Debug, Clone, Parse)]
+enum Emphasis {
+    #[parse(token = "**")]
+    Strong,
+    #[parse(token = "*")]
+    Regular,
+}
+
+#[derive(Debug, Clone, Parse)]
+struct Heading {
+    #[parse(some)]
+    marks: Vec<Hash>,
+    #[parse(token = " ")]
+    space: Text,
+    #[parse(many, char_if = |x| x != '\n')]
+    title: Vec<FatChar>,
+}
+
+#[derive(Debug, Clone, Parse)]
+#[parse(token = "#")]
+struct Hash;
+
+fn render(expr: &Expr) -> String {
+    match expr {
+        Expr::Call(name, args) => {
+            let args = args.iter().map(render).collect::<Vec<_>>().join(" ");
+            format!("({} {args})", name.text)
+        }
+        Expr::Negate(x) => format!("-{}", render(x)),
+        Expr::Atom(x) => x.text.to_string(),
+    }
+}
+
+#[test]
+fn structs_parse_fields_in_order() {
+    let (output, rest) = run("[ docs ] (home)!", Link::parser());
+    let link = output.unwrap();
+    assert_eq!(link.label.text.to_string(), "docs");
+    assert_eq!(link.target.text.to_string(), "home");
+    assert!(!link.visited);
+    assert_eq!(rest, "!");
+}
+
+#[test]
+fn enums_try_variants_in_order() {
+    let (output, _) = run("f(a -g(b) c)", Expr::parser());
+    assert_eq!(render(&output.unwrap()), "(f a -(g b) c)");
+    let (output, rest) = run("**", Emphasis::parser());
+    assert!(matches!(output.unwrap(), Emphasis::Strong));
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn repetition_collects_into_the_field_type() {
+    let (output, rest) = run("## Title\nbody", Heading::parser());
+    let heading = output.unwrap();
+    assert_eq!(heading.marks.len(), 2);
+    assert_eq!(heading.space.to_string(), " ");
+    assert_eq!(Text::from_iter(heading.title).to_string(), "Title");
+    assert_eq!(rest, "\nbody");
+    let (output, _) = run("Title", Heading::parser());
+    assert!(output.is_none());
+}
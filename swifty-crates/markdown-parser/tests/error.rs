@@ -0,0 +1,64 @@
+use markdown_parser::error::{Expected, ParseError};
+use markdown_parser::parser::*;
+use markdown_parser::text::PositionIndex;
+
+fn at(scalar_offset: usize) -> PositionIndex {
+    PositionIndex { scalar_offset, byte_offset: scalar_offset, ..PositionIndex::ZERO }
+}
+
+fn expected(error: &ParseError) -> Vec<Expected> {
+    error.expected.iter().cloned().collect()
+}
+
+#[test]
+fn merge_keeps_the_furthest_error() {
+    let near = ParseError::new(at(1), Some('a')).with_expected([Expected::Char('x')]);
+    let far = ParseError::new(at(3), Some('b')).with_expected([Expected::Char('y')]);
+    assert_eq!(near.clone().merge(far.clone()), far);
+    assert_eq!(far.clone().merge(near), far);
+}
+
+#[test]
+fn merge_at_the_same_position_unions_expected() {
+    let left = ParseError::new(at(2), Some('a'))
+        .with_expected([Expected::Char('x'), Expected::Token("yz".to_owned())]);
+    let right = ParseError::new(at(2), Some('a'))
+        .with_expected([Expected::Char('x'), Expected::EndOfInput])
+        .with_context(["block".to_owned(), "list".to_owned()]);
+    let merged = left.merge(right);
+    assert_eq!(expected(&merged), [Expected::Char('x'), Expected::Token("yz".to_owned()), Expected::EndOfInput]);
+    assert_eq!(merged.context, ["block", "list"]);
+}
+
+#[test]
+fn alternatives_collect_their_expected_sets() {
+    let parser = CharParser::char('a')
+        .void()
+        .or(TextParser::token("bc").void())
+        .or(UnitParser::end_of_input());
+    let error = Parser::try_evaluate("x", parser).err().unwrap();
+    assert_eq!(error.position, PositionIndex::ZERO);
+    assert_eq!(error.found, Some('x'));
+    assert_eq!(expected(&error), [Expected::Char('a'), Expected::Token("bc".to_owned()), Expected::EndOfInput]);
+    assert_eq!(error.to_string(), "1:1: unexpected 'x', expected one of 'a', \"bc\", end of input");
+}
+
+#[test]
+fn the_furthest_failure_wins_over_earlier_ones() {
+    let parser = TextParser::token("ab")
+        .and(CharParser::char('c'))
+        .void()
+        .or(CharParser::char('a').void());
+    let error = Parser::try_evaluate("ab\nd", parser.and(UnitParser::end_of_input())).err().unwrap();
+    assert_eq!(error.position.scalar_offset, 2);
+    assert_eq!(expected(&error), [Expected::Char('c')]);
+    assert_eq!(error.found, Some('\n'));
+}
+
+#[test]
+fn end_of_input_is_reported_at_the_last_column() {
+    let error = Parser::try_evaluate("a\nb", TextParser::token("a\nb").and(CharParser::char('c'))).err().unwrap();
+    assert_eq!(error.found, None);
+    assert_eq!((error.position.line_offset, error.position.column_offset), (1, 1));
+    assert_eq!(error.to_string(), "2:2: unexpected end of input, expected 'c'");
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use markdown_parser::expression::*;
+use markdown_parser::parser::*;
+use common::run;
+
+/// Renders the parse as an s-expression, to make grouping visible.
+fn calculator() -> Parser<String> {
+    let number = CharParser::char_if(|x| x.is_ascii_digit())
+        .some()
+        .map(|xs| xs.into_iter().map(|x| x.value).collect::<String>());
+    let binary = |l: String, op: markdown_parser::text::Text, r: String| format!("({op} {l} {r})");
+    OperatorTable::default()
+        .prefix(TextParser::token("-"), 3, |op, x| format!("({op} {x})"))
+        .infix(TextParser::token("+"), 1, Associativity::Left, binary)
+        .infix(TextParser::token("-"), 1, Associativity::Left, binary)
+        .infix(TextParser::token("*"), 2, Associativity::Left, binary)
+        .infix(TextParser::token("^"), 4, Associativity::Right, binary)
+        .postfix(TextParser::token("!"), 5, |x, op| format!("({op} {x})"))
+        .build(number)
+}
+
+fn parse(source: &str) -> Option<String> {
+    run(source, calculator()).0
+}
+
+#[test]
+fn precedence() {
+    assert_eq!(parse("1 + 2 * 3").unwrap(), "(+ 1 (* 2 3))");
+    assert_eq!(parse("1 * 2 + 3").unwrap(), "(+ (* 1 2) 3)");
+}
+
+#[test]
+fn associativity() {
+    assert_eq!(parse("1 - 2 - 3").unwrap(), "(- (- 1 2) 3)");
+    assert_eq!(parse("1 ^ 2 ^ 3").unwrap(), "(^ 1 (^ 2 3))");
+}
+
+#[test]
+fn prefix_and_postfix() {
+    assert_eq!(parse("-1 ^ 2").unwrap(), "(- (^ 1 2))");
+    assert_eq!(parse("-1 * 2").unwrap(), "(* (- 1) 2)");
+    assert_eq!(parse("-3!").unwrap(), "(- (! 3))");
+}
+
+#[test]
+fn longest_operator_wins() {
+    let number = CharParser::char_if(|x| x.is_ascii_digit()).map(|x| x.value.to_string());
+    let parser = OperatorTable::default()
+        .postfix(TextParser::token("!"), 5, |x, op| format!("({op} {x})"))
+        .infix(TextParser::token("!="), 1, Associativity::Left, |l, op, r| format!("({op} {l} {r})"))
+        .infix(TextParser::token("="), 1, Associativity::Left, |l, op, r| format!("({op} {l} {r})"))
+        .build(number);
+    assert_eq!(run("1 != 2", parser.clone()).0.unwrap(), "(!= 1 2)");
+    assert_eq!(run("1! = 2", parser).0.unwrap(), "(= (! 1) 2)");
+}
+
+#[test]
+fn parentheses_and_whitespace() {
+    assert_eq!(parse("( 1+2 )*3").unwrap(), "(* (+ 1 2) 3)");
+    let (output, rest) = run("1 + 2 ", calculator());
+    assert_eq!(output.unwrap(), "(+ 1 2)");
+    assert_eq!(rest, " ");
+}
+
+#[test]
+fn incomplete_expressions_fail() {
+    assert!(parse("1 +").is_none());
+    assert!(parse("(1 + 2").is_none());
+}
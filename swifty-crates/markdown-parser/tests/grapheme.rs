@@ -0,0 +1,58 @@
+mod common;
+
+use markdown_parser::parser::*;
+use common::run;
+
+const FAMILY: &str = "👨\u{200d}👩\u{200d}👧";
+
+#[test]
+fn next_keeps_clusters_whole() {
+    let source = format!("e\u{301}{FAMILY}x");
+    let (output, rest) = run(&source, TextParser::next_grapheme().many());
+    let graphemes = output.unwrap().iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(graphemes, ["e\u{301}", FAMILY, "x"]);
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn char_parser_splits_clusters() {
+    let (output, rest) = run("e\u{301}", CharParser::next());
+    assert_eq!(output.unwrap().value, 'e');
+    assert_eq!(rest, "\u{301}");
+}
+
+#[test]
+fn positions() {
+    let source = format!("a{FAMILY}b");
+    let parser = TextParser::next_grapheme().keep(TextParser::next_grapheme()).and(TextParser::next_grapheme());
+    let (output, _) = run(&source, parser);
+    let (family, b) = output.unwrap();
+    assert_eq!(family.start_index().unwrap().grapheme_offset, 1);
+    assert_eq!(family.start_index().unwrap().scalar_offset, 1);
+    assert_eq!(b.start_index().unwrap().grapheme_offset, 2);
+    assert_eq!(b.start_index().unwrap().column_offset, 2);
+}
+
+#[test]
+fn grapheme_and_grapheme_if() {
+    let (output, rest) = run("e\u{301}!", TextParser::grapheme("e\u{301}"));
+    assert_eq!(output.unwrap().to_string(), "e\u{301}");
+    assert_eq!(rest, "!");
+    assert!(run("e\u{301}!", TextParser::grapheme("e")).0.is_none());
+    let emoji = TextParser::grapheme_if(|x| x.chars().count() > 1);
+    assert_eq!(run(FAMILY, emoji.clone()).0.unwrap().to_string(), FAMILY);
+    assert!(run("a", emoji).0.is_none());
+}
+
+#[test]
+fn grapheme_token_respects_boundaries() {
+    assert_eq!(run("e\u{301}", TextParser::token("e")).1, "\u{301}");
+    let (output, rest) = run("e\u{301}", TextParser::grapheme_token("e"));
+    assert!(output.is_none());
+    assert_eq!(rest, "e\u{301}");
+    let (output, rest) = run("cafe\u{301} au lait", TextParser::grapheme_token("cafe\u{301}"));
+    assert_eq!(output.unwrap().to_string(), "cafe\u{301}");
+    assert_eq!(rest, " au lait");
+    assert!(run("👨", TextParser::grapheme_token("👨")).0.is_some());
+    assert!(run(FAMILY, TextParser::grapheme_token("👨")).0.is_none());
+}
@@ -0,0 +1,43 @@
+use markdown_parser::error::Expected;
+use markdown_parser::parser::*;
+
+fn heading() -> TextParser {
+    TextParser::token("#").or(TextParser::token("##")).label("atx heading")
+}
+
+#[test]
+fn label_replaces_expected_tokens() {
+    let error = Parser::try_evaluate("x", heading()).err().unwrap();
+    assert_eq!(error.expected.into_iter().collect::<Vec<_>>(), [Expected::Label("atx heading".to_owned())]);
+}
+
+#[test]
+fn label_merges_with_sibling_alternatives() {
+    let parser = heading().or(TextParser::token(">"));
+    let error = Parser::try_evaluate("x", parser).err().unwrap();
+    assert_eq!(error.to_string(), "1:1: unexpected 'x', expected one of \">\", atx heading");
+}
+
+#[test]
+fn label_keeps_errors_past_the_start() {
+    let parser = TextParser::token("#").and(CharParser::char(' ')).label("atx heading");
+    let error = Parser::try_evaluate("#x", parser).err().unwrap();
+    assert_eq!(error.position.scalar_offset, 1);
+    assert!(error.expected.contains(&Expected::Char(' ')));
+}
+
+#[test]
+fn context_frames_chain_in_failures() {
+    let title = CharParser::char('!').context("title");
+    let parser = TextParser::token("# ").and(title).context("atx heading").context("document");
+    let error = Parser::try_evaluate("# x", parser).err().unwrap();
+    assert_eq!(error.context, ["document", "atx heading", "title"]);
+    assert!(error.to_string().ends_with("(while parsing document > atx heading > title)"));
+}
+
+#[test]
+fn context_is_popped_after_the_parser() {
+    let parser = TextParser::token("a").context("first").and(CharParser::char('b'));
+    let error = Parser::try_evaluate("ac", parser).err().unwrap();
+    assert!(error.context.is_empty());
+}
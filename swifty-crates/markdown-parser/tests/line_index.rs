@@ -0,0 +1,46 @@
+use unicode_segmentation::UnicodeSegmentation;
+use markdown_parser::line_index::LineIndex;
+use markdown_parser::text::PositionIndex;
+
+/// Walks the source the slow way, one scalar at a time.
+fn reference(source: &str) -> Vec<PositionIndex> {
+    let mut position = PositionIndex::ZERO;
+    let mut positions = Vec::new();
+    for (byte, grapheme) in source.grapheme_indices(true) {
+        for (offset, char) in grapheme.char_indices() {
+            position.byte_offset = byte + offset;
+            positions.push(position);
+            position.scalar_offset += 1;
+            if char == '\n' {
+                position.line_offset += 1;
+                position.column_offset = 0;
+            }
+        }
+        position.grapheme_offset += 1;
+        if !grapheme.contains('\n') {
+            position.column_offset += 1;
+        }
+    }
+    position.byte_offset = source.len();
+    positions.push(position);
+    positions
+}
+
+#[test]
+fn matches_a_scalar_by_scalar_walk() {
+    let source = "a\r\nbe\u{301}x\n\n🇺🇸z\té\nend";
+    let index = LineIndex::new(source);
+    let boundaries = source.char_indices().map(|(x, _)| x).chain([source.len()]);
+    for (byte, expected) in boundaries.zip(reference(source)) {
+        assert_eq!(index.position(byte), expected, "at byte {byte}");
+    }
+}
+
+#[test]
+fn line_lookups() {
+    let index = LineIndex::new("ab\ncd\n");
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.line_start(1), Some(3));
+    assert_eq!(index.line_column(4), (1, 1));
+    assert_eq!(index.line_column(6), (2, 0));
+}
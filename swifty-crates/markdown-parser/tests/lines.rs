@@ -0,0 +1,113 @@
+mod common;
+
+use markdown_parser::parser::*;
+use common::run;
+
+fn indentation() -> TextParser {
+    CharParser::char(' ').some().map(markdown_parser::text::Text::from_iter)
+}
+
+#[test]
+fn lines_strips_prefixes_and_keeps_positions() {
+    let parser = UnitParser::lines(TextParser::token("> "), ControlFlowParser::noop(), true);
+    let (output, rest) = run("> a\n> b\nc", parser);
+    let lines = output.unwrap();
+    let prefixes = lines.line_starts.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(prefixes, ["> ", "> "]);
+    assert_eq!(lines.content.to_string(), "a\nb");
+    let b = lines.content.iter().find(|x| x.value == 'b').unwrap();
+    assert_eq!((b.index.line_offset, b.index.column_offset), (1, 2));
+    assert_eq!(rest, "\nc");
+}
+
+#[test]
+fn lines_without_trim_keeps_prefixes() {
+    let parser = UnitParser::lines(TextParser::token("> "), ControlFlowParser::noop(), false);
+    let (output, _) = run("> a\n> b\n", parser);
+    assert_eq!(output.unwrap().content.to_string(), "> a\n> b");
+}
+
+#[test]
+fn lines_stop_when_the_prefix_column_changes() {
+    let parser = UnitParser::lines(indentation(), ControlFlowParser::noop(), true);
+    let (output, rest) = run("  a\n  b\n    c", parser);
+    assert_eq!(output.unwrap().content.to_string(), "a\nb");
+    assert_eq!(rest, "\n    c");
+}
+
+#[test]
+fn lines_stop_at_the_terminator() {
+    let terminator = ControlFlowParser::terminate_if_ok(&TextParser::token("> ---"));
+    let parser = UnitParser::lines(TextParser::token("> "), terminator, true);
+    let (output, rest) = run("> a\n> ---\n", parser);
+    assert_eq!(output.unwrap().line_starts.len(), 1);
+    assert_eq!(rest, "\n> ---\n");
+}
+
+#[test]
+fn lines_with_empty_content() {
+    let parser = UnitParser::lines(TextParser::token(">"), ControlFlowParser::noop(), true);
+    let (output, rest) = run(">", parser);
+    let lines = output.unwrap();
+    assert!(lines.content.is_empty());
+    assert_eq!(lines.content.position().byte_offset, 1);
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn lines_need_at_least_one_line() {
+    let parser = UnitParser::lines(TextParser::token("> "), ControlFlowParser::noop(), true);
+    let (output, _) = run("a", parser);
+    assert!(output.is_none());
+}
+
+#[test]
+fn whole_indented_block_deindents() {
+    let item = TextParser::token("- ").keep(TextParser::whole_indented_block(true));
+    let (output, rest) = run("- one\n  two\n\n  three\nfour", item);
+    let block = output.unwrap();
+    assert_eq!(block.to_string(), "one\ntwo\n\nthree");
+    let t = block.iter().find(|x| x.value == 't').unwrap();
+    assert_eq!((t.index.line_offset, t.index.column_offset), (1, 2));
+    assert_eq!(rest, "\nfour");
+}
+
+#[test]
+fn whole_indented_block_can_keep_indentation() {
+    let item = TextParser::token("- ").keep(TextParser::whole_indented_block(false));
+    let (output, _) = run("- one\n  two\nfour", item);
+    assert_eq!(output.unwrap().to_string(), "one\n  two");
+}
+
+#[test]
+fn lines_skip_and_fail() {
+    let blank = TextParser::token(">\n");
+    let parser = || UnitParser::lines(TextParser::token("> "), ControlFlowParser::skip_on(blank.clone()), true);
+    let (output, rest) = run("> a\n>\n> b\n", parser());
+    let lines = output.unwrap();
+    assert_eq!(lines.line_starts.len(), 2);
+    assert_eq!(lines.content.to_string(), "a\nb");
+    assert_eq!(rest, "\n");
+    let (output, rest) = run("> a\n>\nc", parser());
+    assert_eq!(output.unwrap().content.to_string(), "a");
+    assert_eq!(rest, "\n>\nc");
+    let parser = UnitParser::lines(TextParser::token("> "), ControlFlowParser::fail_on(TextParser::token("> !")), true);
+    assert!(run("> a\n> !\n", parser).0.is_none());
+}
+
+#[test]
+fn lines_consume_a_matching_terminator() {
+    let terminator = ControlFlowParser::terminate_on(TextParser::token("> ---\n"));
+    let parser = UnitParser::lines(TextParser::token("> "), terminator, true);
+    let (output, rest) = run("> a\n> ---\nb", parser);
+    assert_eq!(output.unwrap().content.to_string(), "a");
+    assert_eq!(rest, "b");
+}
+
+#[test]
+fn lines_skip_pushed_back_input() {
+    let pushed = markdown_parser::text::Text::initialize_from("#\n");
+    let parser = UnitParser::lines(TextParser::token("> "), ControlFlowParser::skip_on(TextParser::token("#\n")), true);
+    let (output, _) = run("> a", parser.put_back(pushed));
+    assert_eq!(output.unwrap().content.to_string(), "a");
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use markdown_parser::parser::*;
+use common::run;
+
+fn whitespace() -> CharParser {
+    CharParser::char_if(char::is_whitespace)
+}
+
+#[test]
+fn peek_does_not_consume() {
+    let (output, rest) = run("**a", TextParser::token("**").peek());
+    assert_eq!(output.unwrap().to_string(), "**");
+    assert_eq!(rest, "**a");
+}
+
+#[test]
+fn followed_by_leaves_the_lookahead_in_place() {
+    let parser = CharParser::char('*').followed_by(CharParser::char('a'));
+    let (output, rest) = run("*a", parser.clone());
+    assert_eq!(output.unwrap().value, '*');
+    assert_eq!(rest, "a");
+    let (output, _) = run("* a", parser);
+    assert!(output.is_none());
+}
+
+#[test]
+fn not_followed_by_rejects_the_lookahead() {
+    let parser = CharParser::char('*').not_followed_by(whitespace());
+    let (output, rest) = run("*a", parser.clone());
+    assert_eq!(output.unwrap().value, '*');
+    assert_eq!(rest, "a");
+    let (output, _) = run("* a", parser.clone());
+    assert!(output.is_none());
+    let (output, rest) = run("*", parser);
+    assert_eq!(output.unwrap().value, '*');
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn not_followed_by_keeps_lookahead_failures_out_of_errors() {
+    let parser = CharParser::char('*')
+        .not_followed_by(TextParser::token("  x"))
+        .and(CharParser::char('!'));
+    let error = Parser::try_evaluate("*  y", parser).err().unwrap();
+    assert_eq!(error.position.scalar_offset, 1);
+}
+
+#[test]
+fn end_of_input_only_matches_at_the_end() {
+    let (output, _) = run("", UnitParser::end_of_input());
+    assert!(output.is_some());
+    let parser = TextParser::token("ab").ignore(UnitParser::end_of_input());
+    assert!(run("ab", parser.clone()).0.is_some());
+    assert!(run("abc", parser).0.is_none());
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use markdown_parser::parser::*;
+use markdown_parser::text::Text;
+
+#[test]
+fn memoized_parser_runs_once_per_position() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let word = {
+        let calls = calls.clone();
+        TextParser::token("ab").map(move |x| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            x
+        })
+    };
+    let word = word.memoize();
+    let parser = Parser::options([
+        word.clone().ignore(CharParser::char('!')).void(),
+        word.clone().ignore(CharParser::char('?')).void(),
+        word.void(),
+    ]);
+    let (output, state) = Parser::evaluate("ab.", parser);
+    assert!(output.is_some());
+    assert_eq!(state.text.to_string(), ".");
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn memoized_failures_are_cached_too() {
+    let parser = TextParser::token("ab").memoize();
+    let parser = parser.clone().or(parser).or(TextParser::token("ac"));
+    let (output, _) = Parser::evaluate("ac", parser);
+    assert_eq!(output.unwrap().to_string(), "ac");
+}
+
+#[test]
+fn bounded_subparses_have_their_own_cache() {
+    let rest = CharParser::next().many().map(markdown_parser::text::Text::from_iter).memoize();
+    let quote = UnitParser::lines(TextParser::token("> "), ControlFlowParser::noop(), true).map(|x| x.content);
+    let parser = TextParser::token("> ")
+        .keep(rest.clone())
+        .ignore(TextParser::token("never"))
+        .or(UnitParser::bounded(quote, rest));
+    let (output, _) = Parser::evaluate("> a\n> b", parser);
+    assert_eq!(output.unwrap().to_string(), "a\nb");
+}
+
+#[test]
+fn memoized_parsers_keep_their_diagnostics() {
+    let entry = TextParser::token("x").recover_to_next_line(|x| x).memoize();
+    let parser = entry.clone().ignore(CharParser::char('!')).or(entry);
+    let (output, state) = Parser::evaluate("y\n", parser);
+    assert_eq!(output.unwrap().to_string(), "y\n");
+    assert_eq!(state.diagnostics().len(), 1);
+}
+
+#[test]
+fn put_back_input_is_cached_separately() {
+    let rest = CharParser::next().many().map(Text::from_iter).memoize();
+    let pushed = Text::initialize_from("a");
+    let parser = rest.clone().ignore(TextParser::token("never")).or(rest.put_back(pushed));
+    let (output, _) = Parser::evaluate("xyz", parser);
+    assert_eq!(output.unwrap().to_string(), "axyz");
+}
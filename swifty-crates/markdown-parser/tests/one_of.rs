@@ -0,0 +1,50 @@
+mod common;
+
+use markdown_parser::error::Expected;
+use markdown_parser::parser::*;
+use common::run;
+
+fn matched(source: &str, parser: TupleParser<usize, markdown_parser::text::Text>) -> (Option<(usize, String)>, String) {
+    let (output, rest) = run(source, parser);
+    (output.map(|(index, text)| (index, text.to_string())), rest)
+}
+
+#[test]
+fn longest_match_wins_regardless_of_order() {
+    let tokens = ["`", "```", "``"];
+    assert_eq!(matched("````x", TextParser::one_of(&tokens)), (Some((1, "```".to_owned())), "`x".to_owned()));
+    assert_eq!(matched("``x", TextParser::one_of(&tokens)), (Some((2, "``".to_owned())), "x".to_owned()));
+    assert_eq!(matched("`x", TextParser::one_of(&tokens)), (Some((0, "`".to_owned())), "x".to_owned()));
+}
+
+#[test]
+fn falls_back_to_a_shorter_token() {
+    let tokens = ["address", "article", "a"];
+    assert_eq!(matched("addr", TextParser::one_of(&tokens)), (Some((2, "a".to_owned())), "ddr".to_owned()));
+    assert_eq!(matched("b", TextParser::one_of(&tokens)), (None, "b".to_owned()));
+}
+
+#[test]
+fn values_and_positions() {
+    let shortcodes = [(":smile:", '😄'), (":smiley:", '😃'), (":sm:", '?')];
+    let parser = TextParser::token("é ").keep(TextParser::one_of_values(shortcodes));
+    let (output, rest) = run("é :smiley: ok", parser);
+    let (value, text) = output.unwrap();
+    assert_eq!(value, '😃');
+    assert_eq!(text.to_string(), ":smiley:");
+    assert_eq!(text.start_index().unwrap().column_offset, 2);
+    assert_eq!(rest, " ok");
+}
+
+#[test]
+fn duplicates_keep_the_first_value() {
+    let parser = TextParser::one_of_values([("div", 1), ("div", 2)]);
+    assert_eq!(run("div", parser).0.unwrap().0, 1);
+}
+
+#[test]
+fn reports_every_token() {
+    let error = Parser::try_evaluate("x", TextParser::one_of(&["pre", "script"])).unwrap_err();
+    let expected = error.expected.into_iter().collect::<Vec<_>>();
+    assert_eq!(expected, [Expected::Token("pre".to_owned()), Expected::Token("script".to_owned())]);
+}
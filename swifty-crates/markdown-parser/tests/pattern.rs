@@ -0,0 +1,59 @@
+#![cfg(feature = "regex")]
+mod common;
+
+use markdown_parser::error::Expected;
+use markdown_parser::parser::*;
+use markdown_parser::text::Text;
+use common::run;
+
+#[test]
+fn matches_at_the_current_position() {
+    let (output, rest) = run("12) item", TextParser::regex(r"\d{1,9}[.)]"));
+    assert_eq!(output.unwrap().to_string(), "12)");
+    assert_eq!(rest, " item");
+}
+
+#[test]
+fn never_searches_ahead() {
+    let (output, rest) = run("item 12)", TextParser::regex(r"\d{1,9}[.)]"));
+    assert!(output.is_none());
+    assert_eq!(rest, "item 12)");
+}
+
+#[test]
+fn keeps_positions() {
+    let parser = TextParser::token("- ").keep(TextParser::regex("[a-zé]+"));
+    let (output, _) = run("- hé llo", parser);
+    let output = output.unwrap();
+    assert_eq!(output.to_string(), "hé");
+    assert_eq!(output.start_index().unwrap().byte_offset, 2);
+    assert_eq!(output.end().unwrap().index.column_offset, 3);
+}
+
+#[test]
+fn named_captures() {
+    let parser = TextParser::regex_captures(r"<(?P<scheme>[a-z]+):(?P<rest>[^>]*)>(?P<missing>!)?");
+    let (output, rest) = run("<https://example.com> after", parser);
+    let output = output.unwrap();
+    assert_eq!(output.text.to_string(), "<https://example.com>");
+    assert_eq!(output.get("scheme").unwrap().to_string(), "https");
+    let path = output.get("rest").unwrap();
+    assert_eq!(path.to_string(), "//example.com");
+    assert_eq!(path.start_index().unwrap().column_offset, 7);
+    assert!(output.get("missing").is_none());
+    assert_eq!(rest, " after");
+}
+
+#[test]
+fn matches_across_spans() {
+    let text = Text::initialize_from("ab").concat(&Text::initialize_from("cd"));
+    let parser = UnitParser::bounded_fork(text, TextParser::regex_captures("a(?P<middle>bc)d"));
+    let (output, _) = run("", parser);
+    assert_eq!(output.unwrap().get("middle").unwrap().to_string(), "bc");
+}
+
+#[test]
+fn reports_the_pattern() {
+    let error = Parser::try_evaluate("x", TextParser::regex("[0-9]+")).unwrap_err();
+    assert!(error.expected.contains(&Expected::Pattern("[0-9]+".to_owned())));
+}
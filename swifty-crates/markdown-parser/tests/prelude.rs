@@ -0,0 +1,86 @@
+mod common;
+
+use markdown_parser::error::Expected;
+use markdown_parser::parser::*;
+use markdown_parser::prelude::*;
+use common::run;
+
+fn text(source: &str, parser: TextParser) -> (Option<String>, String) {
+    let (output, rest) = run(source, parser);
+    (output.map(|x| x.to_string()), rest)
+}
+
+#[test]
+fn char_classes() {
+    let class = |parser: CharParser, source: &str| run(source, parser).0.map(|x| x.value);
+    assert_eq!(class(CharParser::newline(), "\r\n"), Some('\r'));
+    assert_eq!(class(CharParser::space(), "\n"), None);
+    assert_eq!(class(CharParser::space(), "\u{a0}"), Some('\u{a0}'));
+    assert_eq!(class(CharParser::any_whitespace(), "\n"), Some('\n'));
+    assert_eq!(class(CharParser::number(), "٣"), Some('٣'));
+    assert_eq!(class(CharParser::digit(), "٣"), None);
+    assert_eq!(class(CharParser::hex_digit(), "F"), Some('F'));
+    assert_eq!(class(CharParser::letter(), "ß"), Some('ß'));
+    assert_eq!(class(CharParser::letter(), "1"), None);
+}
+
+#[test]
+fn commonmark_punctuation() {
+    for x in "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~".chars() {
+        assert!(is_punctuation(x), "{x:?}");
+    }
+    for x in ['¡', '€', '→', '»'] {
+        assert!(is_punctuation(x), "{x:?}");
+    }
+    for x in ['a', '1', ' ', 'é'] {
+        assert!(!is_punctuation(x), "{x:?}");
+    }
+}
+
+#[test]
+fn whitespace() {
+    assert_eq!(text(" \t\nx", TextParser::spaces()), (Some(" \t".to_owned()), "\nx".to_owned()));
+    assert_eq!(text("x", TextParser::spaces()), (Some(String::new()), "x".to_owned()));
+    assert_eq!(text(" \n x", TextParser::any_whitespace()), (Some(" \n ".to_owned()), "x".to_owned()));
+}
+
+#[test]
+fn rest_of_line() {
+    assert_eq!(text("abc\r\ndef", TextParser::rest_of_line()), (Some("abc".to_owned()), "\r\ndef".to_owned()));
+    assert_eq!(text("abc", TextParser::rest_of_line()), (Some("abc".to_owned()), String::new()));
+    assert_eq!(text("\nabc", TextParser::rest_of_line()).0, None);
+}
+
+#[test]
+fn line_endings() {
+    let line = TextParser::rest_of_line().ignore(TextParser::line_ending());
+    let (output, rest) = run("a\r\nb\nc\rd", line.many());
+    let lines = output.unwrap().iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(lines, ["a", "b", "c"]);
+    assert_eq!(rest, "d");
+}
+
+#[test]
+fn words() {
+    assert_eq!(text("can't stop", TextParser::word()), (Some("can't".to_owned()), " stop".to_owned()));
+    assert_eq!(text("naïve!", TextParser::word()), (Some("naïve".to_owned()), "!".to_owned()));
+    assert_eq!(text(" word", TextParser::word()).0, None);
+    let (output, rest) = run("one two  three, four", TextParser::words());
+    let words = output.unwrap().iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(words, ["one", "two", "three"]);
+    assert_eq!(rest, ", four");
+}
+
+#[test]
+fn labels_failures() {
+    let error = Parser::try_evaluate("x", CharParser::digit()).unwrap_err();
+    assert!(error.expected.contains(&Expected::Label("digit".to_owned())));
+}
+
+#[test]
+fn words_across_pushed_back_input() {
+    let pushed = markdown_parser::text::Text::initialize_from("a".repeat(100));
+    let (output, rest) = text(&format!("{}.b c", "a".repeat(60)), TextParser::word().put_back(pushed));
+    assert_eq!(output.unwrap(), format!("{}.b", "a".repeat(160)));
+    assert_eq!(rest, " c");
+}
@@ -0,0 +1,95 @@
+use markdown_parser::parser::*;
+use markdown_parser::text::Text;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    Entry(String),
+    Error(String),
+}
+
+fn entry() -> Parser<Line> {
+    let word = CharParser::char_if(char::is_alphanumeric).some().map(Text::from_iter);
+    word.clone()
+        .ignore(CharParser::char('='))
+        .and(word)
+        .ignore(CharParser::char('\n').void().or(UnitParser::end_of_input()))
+        .map(|(key, value)| Line::Entry(format!("{key}={value}")))
+}
+
+#[test]
+fn recover_skips_to_the_next_line() {
+    let parser = entry()
+        .recover_to_next_line(|skipped| Line::Error(skipped.to_string()))
+        .many();
+    let (output, diagnostics) = Parser::evaluate_partial("a=1\nb?2\nc=3", parser);
+    assert_eq!(output.unwrap(), [
+        Line::Entry("a=1".to_owned()),
+        Line::Error("b?2\n".to_owned()),
+        Line::Entry("c=3".to_owned()),
+    ]);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!((diagnostic.start.line_offset, diagnostic.start.column_offset), (1, 0));
+    assert_eq!((diagnostic.end.line_offset, diagnostic.end.column_offset), (2, 0));
+    assert_eq!(diagnostic.error.position.scalar_offset, 5);
+    assert_eq!(diagnostic.error.found, Some('?'));
+}
+
+#[test]
+fn recover_skips_to_the_end_without_a_sync_point() {
+    let parser = entry()
+        .recover_to_next_line(|skipped| Line::Error(skipped.to_string()))
+        .many();
+    let (output, diagnostics) = Parser::evaluate_partial("a=1\nb?2", parser);
+    assert_eq!(output.unwrap(), [Line::Entry("a=1".to_owned()), Line::Error("b?2".to_owned())]);
+    assert_eq!(diagnostics.len(), 1);
+}
+
+#[test]
+fn recover_to_blank_line_skips_a_paragraph() {
+    let parser = entry()
+        .recover_to_blank_line(|skipped| Line::Error(skipped.to_string()))
+        .many();
+    let (output, diagnostics) = Parser::evaluate_partial("a?\nb\n\nc=3", parser);
+    assert_eq!(output.unwrap(), [Line::Error("a?\nb\n\n".to_owned()), Line::Entry("c=3".to_owned())]);
+    assert_eq!(diagnostics.len(), 1);
+}
+
+#[test]
+fn abandoned_recoveries_are_not_reported() {
+    let recovered = entry().recover_to_next_line(|skipped| Line::Error(skipped.to_string()));
+    let parser = recovered.ignore(CharParser::char('!')).or(TextParser::token("x?").map(|_| Line::Error(String::new())));
+    let (output, diagnostics) = Parser::evaluate_partial("x?", parser);
+    assert!(output.is_some());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn unrecovered_failures_become_a_final_diagnostic() {
+    let (output, diagnostics) = Parser::evaluate_partial("a?", entry());
+    assert!(output.is_none());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].error.found, Some('?'));
+}
+
+#[test]
+fn recover_inside_bounded_lines() {
+    let quote = UnitParser::lines(TextParser::token("> "), ControlFlowParser::noop(), true).map(|x| x.content);
+    let body = TextParser::token("ok")
+        .ignore(CharParser::char('\n').void().or(UnitParser::end_of_input()))
+        .recover_to_next_line(|skipped| skipped)
+        .many();
+    let (output, _) = Parser::evaluate_partial("> bad line\n> ok\n", UnitParser::bounded(quote, body));
+    let output = output.unwrap().iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(output, ["bad line\n", "ok"]);
+}
+
+#[test]
+fn partial_evaluation_with_user_state() {
+    let line = TextParser::token("ok\n")
+        .ignore(UnitParser::update_user_state(|count: &usize| count + 1))
+        .recover_to_next_line(|x| x);
+    let (output, diagnostics) = Parser::evaluate_partial_with("ok\nbad\nok\n", 0, line.many());
+    assert_eq!(output.unwrap().len(), 3);
+    assert_eq!(diagnostics.len(), 1);
+}
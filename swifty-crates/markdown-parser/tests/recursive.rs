@@ -0,0 +1,81 @@
+mod common;
+
+use std::sync::Arc;
+use markdown_parser::parser::*;
+use common::run;
+
+/// `group := "[" group* "]"`, returning the deepest nesting.
+fn brackets() -> Parser<usize> {
+    Parser::recursive(|group| {
+        group
+            .many()
+            .between2(CharParser::char('['), CharParser::char(']'))
+            .map(|(_, children, _)| 1 + children.into_iter().max().unwrap_or(0))
+    })
+}
+
+/// `list := "(" item* ")"`, `item := "x" | list`, as two functions referring
+/// to each other.
+fn list() -> Parser<usize> {
+    Parser::lazy(item)
+        .many()
+        .between2(CharParser::char('('), CharParser::char(')'))
+        .map(|(_, items, _)| items.into_iter().sum())
+}
+
+fn item() -> Parser<usize> {
+    CharParser::char('x').map(|_| 1).or(Parser::lazy(list))
+}
+
+#[test]
+fn recursive_parses_nested_brackets() {
+    let (output, rest) = run("[[][[]]]!", brackets());
+    assert_eq!(output.unwrap(), 3);
+    assert_eq!(rest, "!");
+    assert!(run("[[]", brackets()).0.is_none());
+}
+
+#[test]
+fn lazy_allows_mutual_recursion() {
+    let (output, rest) = run("(x(xx)(()x))", list());
+    assert_eq!(output.unwrap(), 4);
+    assert_eq!(rest, "");
+    assert!(run("(x(x)", list()).0.is_none());
+}
+
+#[test]
+fn recursive_grammars_are_freed() {
+    let marker = Arc::new(());
+    let parser = {
+        let marker = marker.clone();
+        Parser::recursive(move |group| {
+            group
+                .many()
+                .between2(CharParser::char('['), CharParser::char(']'))
+                .map(move |_| Arc::strong_count(&marker))
+        })
+    };
+    assert_eq!(run("[[]]", parser.clone()).0.unwrap(), 2);
+    assert_eq!(Arc::strong_count(&marker), 2);
+    drop(parser);
+    assert_eq!(Arc::strong_count(&marker), 1);
+}
+
+#[test]
+fn lazy_grammars_are_freed() {
+    let marker = Arc::new(());
+    let parser = {
+        let marker = marker.clone();
+        Parser::lazy(move || {
+            let marker = marker.clone();
+            list().map(move |x| {
+                let _ = &marker;
+                x
+            })
+        })
+    };
+    assert_eq!(run("(x)", parser.clone()).0.unwrap(), 1);
+    assert_eq!(Arc::strong_count(&marker), 3);
+    drop(parser);
+    assert_eq!(Arc::strong_count(&marker), 1);
+}
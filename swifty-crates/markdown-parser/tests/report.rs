@@ -0,0 +1,65 @@
+use markdown_parser::parser::*;
+use markdown_parser::report::{Label, Renderer, Report};
+use markdown_parser::text::PositionIndex;
+
+fn position(line_offset: usize, column_offset: usize) -> PositionIndex {
+    PositionIndex { line_offset, column_offset, ..PositionIndex::ZERO }
+}
+
+#[test]
+fn renders_a_parse_error() {
+    let source = "# Title\n#x";
+    let parser = TextParser::token("# ")
+        .and(CharParser::char_if(|x| x != '\n').some())
+        .and(CharParser::char('\n'))
+        .many()
+        .and(TextParser::token("# "))
+        .context("heading");
+    let error = Parser::try_evaluate(source, parser).err().unwrap();
+    let output = Renderer::default()
+        .use_color(false)
+        .file_name("doc.md")
+        .render(source, &Report::from(&error));
+    assert_eq!(output, [
+        "error: unexpected '#'",
+        " --> doc.md:2:1",
+        "  |",
+        "2 | #x",
+        "  | ^ expected \"# \"",
+        "  |",
+        "  = note: while parsing heading",
+    ].join("\n"));
+}
+
+#[test]
+fn renders_multi_line_labels() {
+    let source = "one\ntwo\nthree";
+    let report = Report::warning("odd lines")
+        .with_label(Label::primary(position(0, 1), position(1, 2), "here"));
+    let output = Renderer::default().use_color(false).render(source, &report);
+    assert_eq!(output, [
+        "warning: odd lines",
+        " --> 1:2",
+        "  |",
+        "1 | one",
+        "  |  ^^",
+        "2 | two",
+        "  | ^^ here",
+    ].join("\n"));
+}
+
+#[test]
+fn renders_end_of_input() {
+    let error = Parser::try_evaluate("ab", TextParser::token("abc")).err().unwrap();
+    let output = Renderer::default().use_color(false).render("ab", &Report::from(&error));
+    assert!(output.starts_with("error: unexpected 'a'"));
+    let error = Parser::try_evaluate("ab", TextParser::token("ab").and(CharParser::char('c'))).err().unwrap();
+    let output = Renderer::default().use_color(false).render("ab", &Report::from(&error));
+    assert_eq!(output, [
+        "error: unexpected end of input",
+        " --> 1:3",
+        "  |",
+        "1 | ab",
+        "  |   ^ expected 'c'",
+    ].join("\n"));
+}
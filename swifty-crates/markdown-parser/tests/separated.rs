@@ -0,0 +1,118 @@
+mod common;
+
+use markdown_parser::parser::*;
+use common::run;
+
+fn cell() -> Parser<String> {
+    CharParser::char_if(char::is_alphanumeric)
+        .some()
+        .map(|xs| xs.into_iter().map(|x| x.value).collect())
+}
+
+fn pipe() -> TextParser {
+    TextParser::token("|")
+}
+
+fn number() -> Parser<i64> {
+    CharParser::char_if(|x| x.is_ascii_digit()).map(|x| x.value.to_digit(10).unwrap() as i64)
+}
+
+#[test]
+fn sep_by() {
+    let (output, rest) = run("a|bc|d!", cell().sep_by(pipe()));
+    assert_eq!(output.unwrap(), ["a", "bc", "d"]);
+    assert_eq!(rest, "!");
+    let (output, rest) = run("!", cell().sep_by(pipe()));
+    assert!(output.unwrap().is_empty());
+    assert_eq!(rest, "!");
+}
+
+#[test]
+fn sep_by_leaves_a_trailing_separator() {
+    let (output, rest) = run("a|b|", cell().sep_by(pipe()));
+    assert_eq!(output.unwrap(), ["a", "b"]);
+    assert_eq!(rest, "|");
+}
+
+#[test]
+fn sep_by1_requires_an_item() {
+    assert!(run("|a", cell().sep_by1(pipe())).0.is_none());
+    assert_eq!(run("a", cell().sep_by1(pipe())).0.unwrap(), ["a"]);
+}
+
+#[test]
+fn sep_end_by_consumes_a_trailing_separator() {
+    let (output, rest) = run("a|b|\n", cell().sep_end_by(pipe()));
+    assert_eq!(output.unwrap(), ["a", "b"]);
+    assert_eq!(rest, "\n");
+    let (output, rest) = run("a|b\n", cell().sep_end_by1(pipe()));
+    assert_eq!(output.unwrap(), ["a", "b"]);
+    assert_eq!(rest, "\n");
+    assert!(run("|", cell().sep_end_by1(pipe())).0.is_none());
+}
+
+#[test]
+fn min_and_max() {
+    let settings = || SequenceSettings::default().separator(pipe().void());
+    assert!(run("a|", cell().sequence(settings().min(2).max(3))).0.is_none());
+    let (output, rest) = run("a|b|c|d", cell().sequence(settings().min(2).max(3)));
+    assert_eq!(output.unwrap(), ["a", "b", "c"]);
+    assert_eq!(rest, "|d");
+    let (output, rest) = run("a|b", cell().sequence(settings().max(1).allow_trailing(true)));
+    assert_eq!(output.unwrap(), ["a"]);
+    assert_eq!(rest, "b");
+}
+
+#[test]
+fn until_terminator() {
+    let terminator = ControlFlowParser::terminate_if_ok(&TextParser::token("end"));
+    let settings = SequenceSettings::default()
+        .separator(TextParser::token(" ").void())
+        .until_terminator(terminator);
+    let (output, rest) = run("a b end c", cell().sequence(settings));
+    assert_eq!(output.unwrap(), ["a", "b"]);
+    assert_eq!(rest, " end c");
+}
+
+#[test]
+fn stops_without_progress() {
+    let empty = UnitParser::unit();
+    let (output, rest) = run("abc", CharParser::next().sep_by(empty.clone()));
+    assert_eq!(output.unwrap().len(), 3);
+    assert_eq!(rest, "");
+    let (output, _) = run("abc", UnitParser::unit().sep_by(empty));
+    assert!(output.unwrap().is_empty());
+}
+
+#[test]
+fn chainl1_folds_left() {
+    let minus = TextParser::token("-");
+    let (output, rest) = run("9-3-2+", number().chainl1(minus, |l, _, r| l - r));
+    assert_eq!(output.unwrap(), 4);
+    assert_eq!(rest, "+");
+}
+
+#[test]
+fn chainr1_folds_right() {
+    let minus = TextParser::token("-");
+    let (output, rest) = run("9-3-2-", number().chainr1(minus, |l, _, r| l - r));
+    assert_eq!(output.unwrap(), 8);
+    assert_eq!(rest, "-");
+    assert_eq!(run("7", number().chainr1(TextParser::token("-"), |l, _, r| l - r)).0.unwrap(), 7);
+    assert!(run("-", number().chainl1(TextParser::token("-"), |l, _, r| l - r)).0.is_none());
+}
+
+#[test]
+fn empty_items_between_separators() {
+    let cell = || {
+        CharParser::char_if(char::is_alphanumeric)
+            .many()
+            .map(|xs| xs.into_iter().map(|x| x.value).collect::<String>())
+    };
+    let (output, rest) = run("a||b!", cell().sep_by(pipe()));
+    assert_eq!(output.unwrap(), ["a", "", "b"]);
+    assert_eq!(rest, "!");
+    let (output, rest) = run("|a!", cell().sep_by(pipe()));
+    assert_eq!(output.unwrap(), ["", "a"]);
+    assert_eq!(rest, "!");
+}
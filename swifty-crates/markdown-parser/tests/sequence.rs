@@ -0,0 +1,215 @@
+mod common;
+
+use markdown_parser::parser::*;
+use markdown_parser::text::{FatChar, Text};
+use common::run;
+
+fn item() -> CharParser {
+    CharParser::next()
+}
+
+fn terminator() -> TextParser {
+    TextParser::token("**")
+}
+
+fn collect((items, terminator): (Vec<FatChar>, Option<Text>)) -> (String, Option<String>) {
+    (Text::from_iter(items).to_string(), terminator.map(|x| x.to_string()))
+}
+
+#[test]
+fn many_unless_stops_before_and_consumes_the_terminator() {
+    let (output, rest) = run("ab**cd", item().many_unless(terminator()));
+    assert_eq!(collect(output.unwrap()), ("ab".to_owned(), Some("**".to_owned())));
+    assert_eq!(rest, "cd");
+}
+
+#[test]
+fn many_unless_without_a_terminator() {
+    let (output, rest) = run("abc", item().many_unless(terminator()));
+    assert_eq!(collect(output.unwrap()), ("abc".to_owned(), None));
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn many_unless_on_empty_input() {
+    let (output, _) = run("", item().many_unless(terminator()));
+    assert_eq!(collect(output.unwrap()), (String::new(), None));
+}
+
+#[test]
+fn many_unless_with_the_terminator_first() {
+    let (output, rest) = run("**ab", item().many_unless(terminator()));
+    assert_eq!(collect(output.unwrap()), (String::new(), Some("**".to_owned())));
+    assert_eq!(rest, "ab");
+}
+
+#[test]
+fn some_unless_requires_an_item() {
+    let (output, _) = run("", item().some_unless(terminator()));
+    assert!(output.is_none());
+    let (output, _) = run("**ab", item().some_unless(terminator()));
+    assert!(output.is_none());
+    let (output, rest) = run("a**b", item().some_unless(terminator()));
+    assert_eq!(collect(output.unwrap()), ("a".to_owned(), Some("**".to_owned())));
+    assert_eq!(rest, "b");
+    let (output, _) = run("ab", item().some_unless(terminator()));
+    assert_eq!(collect(output.unwrap()), ("ab".to_owned(), None));
+}
+
+#[test]
+fn many_till_requires_the_terminator() {
+    let (output, rest) = run("ab**cd", item().many_till(terminator()));
+    let (items, terminator_value) = output.unwrap();
+    assert_eq!(Text::from_iter(items).to_string(), "ab");
+    assert_eq!(terminator_value.to_string(), "**");
+    assert_eq!(rest, "cd");
+    let (output, _) = run("abc", item().many_till(terminator()));
+    assert!(output.is_none());
+}
+
+#[test]
+fn many_till_on_empty_input() {
+    let (output, _) = run("", item().many_till(terminator()));
+    assert!(output.is_none());
+}
+
+#[test]
+fn many_till_with_the_terminator_first() {
+    let (output, rest) = run("**ab", item().many_till(terminator()));
+    let (items, terminator_value) = output.unwrap();
+    assert!(items.is_empty());
+    assert_eq!(terminator_value.to_string(), "**");
+    assert_eq!(rest, "ab");
+}
+
+#[test]
+fn some_till_requires_an_item_and_the_terminator() {
+    let (output, _) = run("", item().some_till(terminator()));
+    assert!(output.is_none());
+    let (output, _) = run("**ab", item().some_till(terminator()));
+    assert!(output.is_none());
+    let (output, _) = run("ab", item().some_till(terminator()));
+    assert!(output.is_none());
+    let (output, rest) = run("a**", item().some_till(terminator()));
+    let (items, _) = output.unwrap();
+    assert_eq!(Text::from_iter(items).to_string(), "a");
+    assert_eq!(rest, "");
+}
+
+fn letters(items: Vec<FatChar>) -> String {
+    Text::from_iter(items).to_string()
+}
+
+fn letter() -> CharParser {
+    CharParser::char_if(char::is_alphabetic)
+}
+
+#[test]
+fn sequence_min_and_max() {
+    let settings = SequenceSettings::default().min(2).max(3);
+    assert!(run("a", item().sequence(settings)).0.is_none());
+    let settings = SequenceSettings::default().min(2).max(3);
+    let (output, rest) = run("abcd", item().sequence(settings));
+    assert_eq!(letters(output.unwrap()), "abc");
+    assert_eq!(rest, "d");
+    let settings = SequenceSettings::default().min(0);
+    assert!(run("", item().sequence(settings)).0.unwrap().is_empty());
+    assert!(run("", item().sequence(SequenceSettings::default())).0.is_none());
+    let settings = SequenceSettings::default().allow_empty(false).min(2);
+    assert!(run("a", item().sequence(settings)).0.is_none());
+}
+
+#[test]
+fn sequence_with_a_separator() {
+    let settings = SequenceSettings::default().separator(TextParser::token(",").void());
+    let (output, rest) = run("a,b,c,", letter().sequence(settings));
+    assert_eq!(letters(output.unwrap()), "abc");
+    assert_eq!(rest, ",");
+    let settings = SequenceSettings::default().separator(TextParser::token(",").void());
+    let (output, rest) = run("a,b c", letter().sequence(settings));
+    assert_eq!(letters(output.unwrap()), "ab");
+    assert_eq!(rest, " c");
+}
+
+#[test]
+fn terminator_policies() {
+    let settings = |policy| {
+        SequenceSettings::default()
+            .until_terminator(ControlFlowParser::terminate_on(terminator()))
+            .terminator_policy(policy)
+    };
+    let (output, rest) = run("ab**cd", item().sequence(settings(TerminatorPolicy::Consume)));
+    assert_eq!(letters(output.unwrap()), "ab");
+    assert_eq!(rest, "cd");
+    let (output, rest) = run("ab**cd", item().sequence(settings(TerminatorPolicy::Leave)));
+    assert_eq!(letters(output.unwrap()), "ab");
+    assert_eq!(rest, "**cd");
+    let (output, rest) = run("ab**cd", item().sequence_with_terminator(settings(TerminatorPolicy::Return)));
+    let (items, terminator_text) = output.unwrap();
+    assert_eq!(letters(items), "ab");
+    let terminator_text = terminator_text.unwrap();
+    assert_eq!(terminator_text.to_string(), "**");
+    assert_eq!(terminator_text.start_index().unwrap().byte_offset, 2);
+    assert_eq!(rest, "cd");
+    let (output, _) = run("ab**cd", item().sequence_with_terminator(settings(TerminatorPolicy::Consume)));
+    assert!(output.unwrap().1.is_none());
+}
+
+#[test]
+fn skip_and_fail() {
+    let comment = TextParser::token("#").and(CharParser::char_if(|x| x != '\n').many());
+    let forbidden = TextParser::token("!");
+    let terminator = ControlFlowParser::options(vec![
+        comment.map(|_| ControlFlow::Skip),
+        forbidden.map(|_| ControlFlow::Fail),
+        ControlFlowParser::noop(),
+    ]);
+    let settings = || SequenceSettings::default().until_terminator(terminator.clone());
+    let (output, rest) = run("ab#cd\nef", item().sequence(settings()));
+    assert_eq!(letters(output.unwrap()), "ab\nef");
+    assert_eq!(rest, "");
+    assert!(run("ab!cd", item().sequence(settings())).0.is_none());
+}
+
+#[test]
+fn terminator_constructors() {
+    let settings = SequenceSettings::default().until_terminator(ControlFlowParser::terminate_if_ok(&terminator()));
+    let (output, rest) = run("ab**cd", item().sequence(settings));
+    assert_eq!(letters(output.unwrap()), "ab");
+    assert_eq!(rest, "**cd");
+    let settings = SequenceSettings::default().until_terminator(ControlFlowParser::skip_on(terminator()));
+    let (output, rest) = run("ab**cd", item().sequence(settings));
+    assert_eq!(letters(output.unwrap()), "abcd");
+    assert_eq!(rest, "");
+    let settings = SequenceSettings::default().until_terminator(ControlFlowParser::fail_on(terminator()));
+    assert!(run("ab**cd", item().sequence(settings)).0.is_none());
+}
+
+#[test]
+fn skip_without_progress_parses_an_item() {
+    let settings = SequenceSettings::default().until_terminator(ControlFlowParser::pure(ControlFlow::Skip));
+    let (output, _) = run("ab", item().sequence(settings));
+    assert_eq!(letters(output.unwrap()), "ab");
+}
+
+#[test]
+fn items_that_consume_nothing_end_the_sequence() {
+    let spaces = CharParser::char(' ').many().map(Text::from_iter);
+    let (output, rest) = run("a", spaces.clone().many());
+    assert!(output.unwrap().is_empty());
+    assert_eq!(rest, "a");
+    let (output, rest) = run("  a", TextParser::token(" ").or(spaces).many());
+    assert_eq!(output.unwrap().len(), 2);
+    assert_eq!(rest, "a");
+}
+
+#[test]
+fn progress_is_measured_on_the_input_not_its_positions() {
+    let pushed = Text::initialize_from("a");
+    let (output, rest) = run("xyz", item().many().put_back(pushed.clone()));
+    assert_eq!(letters(output.unwrap()), "axyz");
+    assert_eq!(rest, "");
+    let settings = SequenceSettings::default().until_terminator(ControlFlowParser::skip_on(TextParser::token("a")));
+    let (output, _) = run("xyz", item().sequence(settings).put_back(pushed));
+    assert_eq!(letters(output.unwrap()), "xyz");
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "sync")]
+mod common;
+
+use std::thread;
+use markdown_parser::expression::*;
+use markdown_parser::parser::*;
+use markdown_parser::text::Text;
+use common::run;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn sum() -> Parser<u64> {
+    let number = CharParser::char_if(|x| x.is_ascii_digit())
+        .some()
+        .map(|xs| xs.into_iter().map(|x| x.value).collect::<String>().parse().unwrap());
+    OperatorTable::default()
+        .infix(TextParser::token("+"), 1, Associativity::Left, |l, _, r| l + r)
+        .infix(TextParser::token("*"), 2, Associativity::Left, |l, _, r| l * r)
+        .build(number)
+}
+
+#[test]
+fn types_are_send_sync() {
+    assert_send_sync::<Parser<Text>>();
+    assert_send_sync::<Parser<Vec<Text>, u32>>();
+    assert_send_sync::<Text>();
+    assert_send_sync::<OperatorTable<u64>>();
+}
+
+#[test]
+fn shared_grammar_across_threads() {
+    let grammar = sum();
+    let results = thread::scope(|scope| {
+        let handles = (0..8u64)
+            .map(|n| {
+                let grammar = grammar.clone();
+                scope.spawn(move || run(&format!("{n} * 2 + 1"), grammar))
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().map(|x| x.join().unwrap()).collect::<Vec<_>>()
+    });
+    for (n, (output, rest)) in results.into_iter().enumerate() {
+        assert_eq!(output, Some(n as u64 * 2 + 1));
+        assert_eq!(rest, "");
+    }
+}
+
+#[test]
+fn text_moves_between_threads() {
+    let text = Text::initialize_from("héllo\nwörld");
+    let (_, rest) = text.take(6).unwrap();
+    let position = thread::spawn(move || rest.position()).join().unwrap();
+    assert_eq!((position.line_offset, position.column_offset), (1, 0));
+}
@@ -0,0 +1,89 @@
+use markdown_parser::text::{FatChar, Text};
+
+fn line_column(text: &Text) -> (usize, usize) {
+    let position = text.position();
+    (position.line_offset, position.column_offset)
+}
+
+#[test]
+fn slicing_keeps_positions() {
+    let text = Text::initialize_from("ab\ncdé f");
+    let (leading, rest) = text.take(4).unwrap();
+    assert_eq!(leading.to_string(), "ab\nc");
+    assert_eq!(line_column(&rest), (1, 1));
+    let (prefix, rest) = rest.pop_prefix("dé").unwrap();
+    assert_eq!(prefix.end_index().unwrap().byte_offset, 5);
+    assert_eq!(rest.position().byte_offset, 7);
+    assert_eq!(rest.to_string(), " f");
+    assert!(text.take(9).is_none());
+}
+
+#[test]
+fn end_of_input_position() {
+    let text = Text::initialize_from("a\nb");
+    let (_, rest) = text.take(3).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(line_column(&rest), (1, 1));
+    assert_eq!(rest.position().byte_offset, 3);
+}
+
+#[test]
+fn collected_and_concatenated_text() {
+    let text = Text::initialize_from("> a\n> b");
+    let content = text.iter().filter(|x| x.index.column_offset >= 2).collect::<Text>();
+    assert_eq!(content.to_string(), "a\nb");
+    let joined = content.concat(&text);
+    assert_eq!(joined.to_string(), "a\nb> a\n> b");
+    assert!(joined.has_prefix("a\nb> "));
+    let (_, rest) = joined.pop_prefix("a\nb>").unwrap();
+    assert_eq!(line_column(&rest), (0, 1));
+    let (head, _) = rest.uncons().unwrap();
+    assert_eq!(head.value, ' ');
+}
+
+#[test]
+fn empty_text_can_be_sliced() {
+    let empty = Text::from_iter(Vec::<FatChar>::new());
+    assert!(empty.is_empty());
+    let (leading, rest) = empty.take(0).unwrap();
+    assert!(leading.is_empty() && rest.is_empty());
+    assert!(empty.take(1).is_none());
+    let (content, trailing) = empty.trim_trailing(true);
+    assert!(content.is_empty() && trailing.is_empty());
+    assert!(empty.uncons().is_none());
+    assert_eq!(empty.position(), markdown_parser::text::PositionIndex::ZERO);
+    let other = Text::initialize_from("ab");
+    assert_eq!(empty.concat(&other).to_string(), "ab");
+    assert_eq!(other.concat(&empty).to_string(), "ab");
+}
+
+#[test]
+fn empty_slices_keep_their_position() {
+    let text = Text::initialize_from("ab\ncd");
+    let (_, rest) = text.take(5).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(line_column(&rest), (1, 2));
+    let (empty, _) = text.take(3).unwrap().1.take(0).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(line_column(&empty), (1, 0));
+}
+
+#[test]
+fn collected_text_keeps_every_position() {
+    let text = Text::initialize_from("> ab\r\n> é\u{301}x\n>  c\n");
+    let chars = text.iter().filter(|x| x.index.column_offset >= 2).collect::<Vec<_>>();
+    let collected = Text::from_iter(chars.clone());
+    let positions = |xs: Vec<FatChar>| xs.into_iter().map(|x| x.index).collect::<Vec<_>>();
+    assert_eq!(positions(collected.iter().collect()), positions(chars.clone()));
+    assert_eq!(collected.position(), chars[0].index);
+    let (_, rest) = collected.take(chars.len()).unwrap();
+    assert_eq!(rest.position(), chars.last().unwrap().end_index());
+}
+
+#[test]
+fn fat_char_lists_collect_into_text() {
+    let list = Text::initialize_from("ab").iter().collect::<markdown_parser::text::FatCharList>();
+    let text = Text::from_iter(list);
+    assert_eq!(text.to_string(), "ab");
+    assert_eq!(text.end().unwrap().index.scalar_offset, 1);
+}
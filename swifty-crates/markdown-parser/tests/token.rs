@@ -0,0 +1,71 @@
+mod common;
+
+use markdown_parser::parser::*;
+use markdown_parser::token::TokenSettings;
+use common::run;
+
+fn text(source: &str, parser: TextParser) -> (Option<String>, String) {
+    let (output, rest) = run(source, parser);
+    (output.map(|x| x.to_string()), rest)
+}
+
+#[test]
+fn ignore_case_keeps_the_original() {
+    let (output, rest) = run("<DiV class>", TextParser::token("<").keep(TextParser::token_ignore_case("div")));
+    let output = output.unwrap();
+    assert_eq!(output.to_string(), "DiV");
+    assert_eq!(output.start_index().unwrap().byte_offset, 1);
+    assert_eq!(rest, " class>");
+}
+
+#[test]
+fn unicode_case_folding() {
+    assert_eq!(text("ΣΊΣΥΦΟΣ", TextParser::token_ignore_case("σίσυφος")).0.unwrap(), "ΣΊΣΥΦΟΣ");
+    assert_eq!(text("STRASSE!", TextParser::token_ignore_case("straße")), (Some("STRASSE".to_owned()), "!".to_owned()));
+    assert_eq!(text("Straße", TextParser::token_ignore_case("STRASSE")).0.unwrap(), "Straße");
+    assert_eq!(text("ß", TextParser::token_ignore_case("s")).0, None);
+}
+
+#[test]
+fn normalization() {
+    let nfc = "caf\u{e9}";
+    let nfd = "cafe\u{301}";
+    let settings = TokenSettings::default().normalize(true);
+    assert_eq!(text(nfd, TextParser::token(nfc)).0, None);
+    assert_eq!(text(&format!("{nfd}!"), TextParser::token_with(nfc, settings)), (Some(nfd.to_owned()), "!".to_owned()));
+    assert_eq!(text(nfc, TextParser::token_with(nfd, settings)).0.unwrap(), nfc);
+    assert_eq!(text(nfc, TextParser::token_with("CAFE\u{301}", settings)).0, None);
+    let both = settings.fold_case(true);
+    assert_eq!(text(nfc, TextParser::token_with("CAFE\u{301}", both)).0.unwrap(), nfc);
+}
+
+#[test]
+fn never_ends_inside_a_grapheme() {
+    let settings = TokenSettings::default().normalize(true);
+    assert_eq!(text("cafe\u{301}", TextParser::token_with("cafe", settings)).0, None);
+    assert_eq!(text("cafe\u{301}", TextParser::token_ignore_case("CAFE")).0, None);
+}
+
+#[test]
+fn default_settings_compare_exactly() {
+    let exact = TokenSettings::default();
+    assert_eq!(text("Div", TextParser::token_with("div", exact)).0, None);
+    assert_eq!(text("div", TextParser::token_with("div", exact)).0.unwrap(), "div");
+    assert_eq!(text("", TextParser::token_with("", exact)).0.unwrap(), "");
+}
+
+#[test]
+fn tokens_across_pushed_back_input() {
+    let pushed = markdown_parser::text::Text::initialize_from("DI");
+    let parser = TextParser::token_ignore_case("dive").put_back(pushed);
+    assert!(run("VE\u{301}x", parser.clone()).0.is_none());
+    let (output, rest) = run("VEx", parser);
+    assert_eq!(output.unwrap().to_string(), "DIVE");
+    assert_eq!(rest, "x");
+    let long = "é".repeat(80);
+    let pushed = markdown_parser::text::Text::initialize_from(&long[..100]);
+    let parser = TextParser::token_ignore_case(long.to_uppercase()).put_back(pushed);
+    let (output, rest) = run(&format!("{}x", &long[100..]), parser);
+    assert_eq!(output.unwrap().to_string(), long);
+    assert_eq!(rest, "x");
+}
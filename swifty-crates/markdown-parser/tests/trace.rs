@@ -0,0 +1,61 @@
+use markdown_parser::parser::*;
+use markdown_parser::trace::Outcome;
+
+#[test]
+fn evaluate_traced_records_nested_attempts() {
+    let marker = TextParser::token("#").traced("marker");
+    let title = CharParser::next().some().traced("title");
+    let parser = marker.and(title).traced("heading");
+    let (output, _, trace) = Parser::evaluate_traced("#Hi", parser);
+    assert!(output.is_some());
+    assert_eq!(trace.entries.len(), 1);
+    let heading = &trace.entries[0];
+    assert_eq!(heading.label, "heading");
+    assert_eq!(heading.outcome, Outcome::Ok);
+    assert_eq!(heading.preview, "#Hi");
+    assert_eq!((heading.start.scalar_offset, heading.end.scalar_offset), (0, 3));
+    let labels = heading.children.iter().map(|x| x.label.as_str()).collect::<Vec<_>>();
+    assert_eq!(labels, ["marker", "title"]);
+}
+
+#[test]
+fn failed_attempts_are_recorded() {
+    let parser = TextParser::token("a").traced("a")
+        .or(TextParser::token("b").traced("b"));
+    let (_, _, trace) = Parser::evaluate_traced("b", parser);
+    let outcomes = trace.entries.iter().map(|x| (x.label.as_str(), x.outcome)).collect::<Vec<_>>();
+    assert_eq!(outcomes, [("a", Outcome::Fail), ("b", Outcome::Ok)]);
+}
+
+#[test]
+fn plain_evaluation_records_nothing() {
+    let parser = TextParser::token("a").traced("a");
+    assert!(Parser::evaluate("a", parser.clone()).0.is_some());
+    for _ in 0..2 {
+        let (_, _, trace) = Parser::evaluate_traced("a", parser.clone());
+        assert_eq!(trace.entries.len(), 1);
+    }
+}
+
+#[test]
+fn previews_show_the_consumed_text() {
+    let quote = UnitParser::lines(TextParser::token("> "), ControlFlowParser::noop(), true).map(|x| x.content);
+    let parser = Parser::bounded(quote, TextParser::token("a\n").traced("first line"));
+    let (_, _, trace) = Parser::evaluate_traced("> a\n> b", parser);
+    assert_eq!(trace.entries[0].preview, "a\n");
+    let parser = CharParser::next().many().traced("everything");
+    let (_, _, trace) = Parser::evaluate_traced("x".repeat(30), parser);
+    assert_eq!(trace.entries[0].preview, format!("{}…", "x".repeat(24)));
+}
+
+#[test]
+fn grammars_with_user_state_can_be_traced() {
+    let parser = TextParser::token("a")
+        .ignore(UnitParser::update_user_state(|count: &usize| count + 1))
+        .traced("a")
+        .many();
+    let (output, state, trace) = Parser::evaluate_traced_with("aa", 10, parser);
+    assert_eq!(output.unwrap().len(), 2);
+    assert_eq!(state.user, 12);
+    assert_eq!(trace.entries.len(), 2);
+}
@@ -0,0 +1,54 @@
+use markdown_parser::parser::*;
+use markdown_parser::text::Text;
+
+/// A code span whose closing backtick run must match the opening one.
+fn code_span() -> TextParser<usize> {
+    let run = || CharParser::<usize>::char('`').some().map(|xs| xs.len());
+    let opening = run().and_then(UnitParser::set_user_state);
+    let closing = run()
+        .and(Parser::user_state())
+        .and_then(|(length, expected)| {
+            if length == expected { UnitParser::unit() } else { UnitParser::fail() }
+        });
+    opening
+        .keep(CharParser::char_if(|x| x != '`').some().map(Text::from_iter))
+        .ignore(closing)
+}
+
+#[test]
+fn user_state_carries_values_between_parsers() {
+    let (output, state) = Parser::evaluate_with("``code``", 0, code_span());
+    assert_eq!(output.unwrap().to_string(), "code");
+    assert_eq!(state.user, 2);
+    let (output, _) = Parser::evaluate_with("``code`", 0, code_span());
+    assert!(output.is_none());
+}
+
+#[test]
+fn backtracking_restores_the_user_state() {
+    let bump = UnitParser::<usize>::update_user_state(|x| x + 1);
+    let parser = bump.clone()
+        .keep(CharParser::char('a'))
+        .or(CharParser::char('b'));
+    let (output, state) = Parser::evaluate_with("b", 0, parser);
+    assert_eq!(output.unwrap().value, 'b');
+    assert_eq!(state.user, 0);
+}
+
+#[test]
+fn with_user_state_is_scoped_to_the_sub_parser() {
+    let depth = Parser::<usize, usize>::user_state();
+    let parser = depth.clone()
+        .with_user_state(|x| x + 1)
+        .and(depth);
+    let (output, _) = Parser::evaluate_with("", 4, parser);
+    assert_eq!(output.unwrap(), (5, 4));
+}
+
+#[test]
+fn memoize_respects_the_user_state() {
+    let depth = Parser::<usize, usize>::user_state().memoize();
+    let parser = depth.clone().and(depth.with_user_state(|x| x * 10));
+    let (output, _) = Parser::evaluate_with("", 3, parser);
+    assert_eq!(output.unwrap(), (3, 30));
+}
@@ -18,8 +18,9 @@ impl<Type> PrettyTreePrinter for Type where Type: ToPrettyTree {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum PrettyTree {
+    #[default]
     Empty,
     /// A terminal leaf node.
     Value(String),
@@ -34,8 +35,7 @@ pub enum PrettyTree {
 impl PrettyTree {
     pub fn empty() -> Self { Self::Empty }
     pub fn value(value: impl ToString) -> Self {
-        let value = value.to_string();
-        Self::Value(format!("{value}"))
+        Self::Value(value.to_string())
     }
     pub fn string<T: ToString>(value: T) -> Self {
         let value = value.to_string();
@@ -88,16 +88,13 @@ impl PrettyTree {
     }
 }
 
-impl Default for PrettyTree {
-    fn default() -> Self { PrettyTree::Empty }
-}
-
 #[derive(Debug, Clone)]
 pub struct PrettyValue {
     pub text: String
 }
 
 impl PrettyValue {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: impl AsRef<str>) -> Self {
         Self { text: value.as_ref().to_owned() }
     }
@@ -130,6 +127,7 @@ pub struct PrettyFragment {
 }
 
 impl PrettyFragment {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_iter<Value: ToPrettyTree>(list: impl IntoIterator<Item = Value>) -> Self {
         Self { nodes: list.into_iter().map(|x| x.to_pretty_tree()).collect() }
     }